@@ -0,0 +1,41 @@
+//! Benchmarks of decoding chunks of samples, e.g. the `U8` samples of rtl-sdr captures.
+
+use std::hint::black_box;
+use criterion::{criterion_main, BenchmarkId, Criterion, Throughput};
+use sdr_iq_file_reader::{SampleType, SdrFileReader};
+
+/// The number of samples in each chunk
+const SAMPLES_PER_CHUNK: usize = 16_384;
+
+/// Decode one chunk of `sample_type` samples, centered and normalized, or as raw values.
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements(SAMPLES_PER_CHUNK as u64));
+    for sample_type in [SampleType::U8, SampleType::I8, SampleType::I16] {
+        let len = SAMPLES_PER_CHUNK * sample_type.sample_len();
+        let bytes = (0..=u8::MAX).cycle().map(|n| n.wrapping_mul(37)).take(len).collect::<Vec<_>>();
+        for normalize in [false, true] {
+            let mut reader = SdrFileReader::from_bytes(bytes.clone(), SAMPLES_PER_CHUNK, sample_type)
+                .center_unsigned(normalize)
+                .normalize(normalize)
+                .build()
+                .unwrap();
+            let mut samples = Vec::with_capacity(SAMPLES_PER_CHUNK);
+            let name = if normalize { "normalized" } else { "raw" };
+            group.bench_function(BenchmarkId::new(name, sample_type), |b| {
+                b.iter(|| {
+                    reader.reset().unwrap();
+                    black_box(reader.read_next_chunk_into_f32(&mut samples).unwrap());
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+// The macro generates a public function without documentation
+#[allow(missing_docs)]
+mod group {
+    criterion::criterion_group!(benches, super::decode);
+}
+criterion_main!(group::benches);
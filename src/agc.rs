@@ -0,0 +1,60 @@
+//! Block-based automatic gain control.
+
+use num_complex::Complex;
+use num_traits::{Float, FromPrimitive};
+use crate::SdrReaderError;
+
+/// An automatic gain control that scales each chunk toward a target RMS level.
+/// The gain carries over between chunks, so it follows slow level changes without jumping at every chunk.
+#[derive(Clone)]
+pub(crate) struct Agc {
+    target_rms: f64,
+    /// The fraction of the distance to the ideal gain covered per chunk when the level rises
+    attack: f64,
+    /// The fraction of the distance to the ideal gain covered per chunk when the level falls
+    decay: f64,
+    gain: f64,
+}
+
+impl Agc {
+    /// Create the AGC for the `agc` option of a reader, a target RMS, an attack and a decay.
+    pub(crate) fn from_options(agc: Option<(f64, f64, f64)>) -> Result<Option<Self>, SdrReaderError> {
+        let Some((target_rms, attack, decay)) = agc else {
+            return Ok(None);
+        };
+        let is_fraction = |value: f64| value > 0.0 && value <= 1.0;
+        if !(target_rms.is_finite() && target_rms > 0.0 && is_fraction(attack) && is_fraction(decay)) {
+            return Err(SdrReaderError::InvalidConfig(
+                "agc needs a positive and finite target RMS, and an attack and decay between 0 and 1".to_string(),
+            ));
+        }
+        Ok(Some(Agc { target_rms, attack, decay, gain: 1.0 }))
+    }
+
+    /// The gain applied to the last chunk.
+    pub(crate) fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    /// Forget the gain, as if no samples had been processed.
+    pub(crate) fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+
+    /// Move the gain toward the one that brings the RMS of `samples` to the target, then scale them by it.
+    /// Silent chunks leave the gain unchanged, so it doesn't grow without bound in pauses of a signal.
+    pub(crate) fn process<T: Float + FromPrimitive>(&mut self, samples: &mut [Complex<T>]) {
+        let power = samples.iter().map(|sample| sample.norm_sqr().to_f64().unwrap_or(f64::NAN)).sum::<f64>();
+        #[allow(clippy::cast_precision_loss)]
+        let rms = (power / samples.len() as f64).sqrt();
+        if rms.is_finite() && rms > 0.0 {
+            let ideal = self.target_rms / rms;
+            let rate = if ideal < self.gain { self.attack } else { self.decay };
+            self.gain += rate * (ideal - self.gain);
+        }
+        let gain = T::from_f64(self.gain).unwrap_or_else(T::nan);
+        for sample in samples {
+            *sample = *sample * gain;
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Reading chunks as `ndarray` arrays.
+
+use std::io::Read;
+use ndarray::{Array1, Array2};
+use num_complex::Complex;
+use crate::{SdrFileReader, SdrReaderError};
+
+impl<R: Read> SdrFileReader<R> {
+    /// Read the next chunk of samples as an [`Array1`] of Complex<f32>.
+    /// The samples are the same as those of [`SdrFileReader::read_next_chunk_complexf32`], without copying them.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`](crate::TrailingPolicy)
+    pub fn read_next_chunk_ndarray_f32(&mut self) -> Result<Option<Array1<Complex<f32>>>, SdrReaderError> {
+        Ok(self.read_next_chunk_complexf32()?.map(Array1::from_vec))
+    }
+
+    /// Read the next `rows` chunks as the rows of an [`Array2`] of Complex<f32>, e.g. the frames of a spectrogram.
+    /// Each chunk must have `cols` samples, so `cols` is usually `samples_per_chunk`. Set `overlap` for overlapping frames.
+    /// The array has fewer rows if the file ends before `rows` chunks are read. A shorter final chunk is dropped.
+    ///
+    /// # Returns
+    /// - `Ok(Some(frames))` if at least one chunk was read
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if a chunk other than the final one doesn't have `cols` samples,
+    ///   e.g. with decimation or resampling
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`](crate::TrailingPolicy)
+    pub fn read_spectrogram_frames(&mut self, rows: usize, cols: usize) -> Result<Option<Array2<Complex<f32>>>, SdrReaderError> {
+        let mut samples = Vec::with_capacity(rows * cols);
+        let mut chunk = Vec::with_capacity(cols);
+        let mut frames = 0;
+        let mut short_len = None;
+        while frames < rows {
+            match (self.read_next_chunk_into(&mut chunk)?, short_len) {
+                (None, _) => break,
+                (Some(len), None) if len == cols => {
+                    samples.extend_from_slice(&chunk);
+                    frames += 1;
+                }
+                // The short chunk is dropped if the next read reaches the end of the file
+                (Some(len), None) if len < cols => short_len = Some(len),
+                // A chunk follows the short one, so it wasn't the final chunk
+                (Some(_), Some(len)) | (Some(len), None) => {
+                    return Err(SdrReaderError::InvalidConfig(format!("The chunks have {len} samples instead of {cols}")))
+                }
+            }
+        }
+        if frames == 0 {
+            return Ok(None);
+        }
+        let frames = Array2::from_shape_vec((frames, cols), samples).map_err(|why| SdrReaderError::InvalidConfig(why.to_string()))?;
+        Ok(Some(frames))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SampleType, TrailingPolicy};
+
+    const FILE_PATH: &str = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+
+    fn build(samples_per_chunk: usize) -> SdrFileReader {
+        SdrFileReader::builder()
+            .file_path(FILE_PATH)
+            .samples_per_chunk(samples_per_chunk)
+            .sample_type(SampleType::F32)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader")
+    }
+
+    #[test]
+    fn test_ndarray_matches_vec() {
+        let (mut vectors, mut arrays) = (build(1000), build(1000));
+        while let Some(chunk) = vectors.read_next_chunk_complexf32().unwrap() {
+            assert_eq!(arrays.read_next_chunk_ndarray_f32().unwrap().unwrap().to_vec(), chunk);
+        }
+        assert_eq!(arrays.read_next_chunk_ndarray_f32().unwrap(), None);
+    }
+
+    #[test]
+    fn test_spectrogram_frames() {
+        // 16384 samples are 16 frames of 1000 samples and a shorter final chunk
+        let mut reader = build(1000);
+        let all = build(1000).read_all_complexf32().unwrap();
+        let frames = reader.read_spectrogram_frames(10, 1000).unwrap().unwrap();
+        assert_eq!(frames.dim(), (10, 1000));
+        assert_eq!(frames.row(3).to_vec(), all[3000..4000]);
+        let frames = reader.read_spectrogram_frames(10, 1000).unwrap().unwrap();
+        assert_eq!(frames.dim(), (6, 1000));
+        assert_eq!(reader.read_spectrogram_frames(10, 1000).unwrap(), None);
+
+        let result = build(1000).read_spectrogram_frames(10, 512);
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+        // A short chunk that isn't the final one is an error as soon as the next chunk is read
+        let mut reader = build(1000);
+        let result = reader.read_spectrogram_frames(10, 2000);
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), all[2000..3000]);
+    }
+}
@@ -0,0 +1,214 @@
+//! Reading samples from a tokio [`AsyncRead`] source.
+
+use std::io::ErrorKind;
+use bon::bon;
+use num_complex::Complex;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use crate::decode::DecodeOptions;
+use crate::{Endianness, SampleType, SdrReaderError};
+
+/// The async counterpart of [`SdrFileReader`](crate::SdrFileReader), reading from any tokio [`AsyncRead`] source,
+/// e.g. a `tokio::fs::File` or a `tokio::net::TcpStream`.
+///
+/// # Example
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use sdr_iq_file_reader::{AsyncSdrFileReader, SampleType};
+/// let file = tokio::fs::File::open("gqrx_20240929_015218_580206500_2400000_fc.raw").await.unwrap();
+/// let mut reader = AsyncSdrFileReader::from_reader(file, 1024, SampleType::F32).build().unwrap();
+/// while let Some(samples) = reader.read_next_chunk_complexf32().await.unwrap() {
+///     println!("Read {} samples", samples.len());
+/// }
+/// # }
+/// ```
+pub struct AsyncSdrFileReader<R> {
+    reader: R,
+    samples_per_chunk: usize,
+    decode: DecodeOptions,
+    buffer: Vec<u8>,
+}
+
+#[bon]
+impl<R: AsyncRead + Unpin> AsyncSdrFileReader<R> {
+    /// Create a new `AsyncSdrFileReader` that reads samples from `reader`.
+    /// The source is not buffered, so wrap it in a `tokio::io::BufReader` if it benefits from buffering.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `samples_per_chunk` is 0
+    /// - `SdrReaderError::InvalidConfig` if `normalize_with` is not positive and finite
+    /// - `SdrReaderError::InvalidConfig` if `bit_shift` is not less than the number of bits of a component
+    /// - `SdrReaderError::InvalidConfig` if the sample type is `F16` and the `half` feature is disabled
+    #[builder(finish_fn = build)]
+    pub fn from_reader(
+        #[builder(start_fn)] reader: R,
+        #[builder(start_fn)] samples_per_chunk: usize,
+        #[builder(start_fn)] sample_type: SampleType,
+        /// The byte order of multi-byte samples. Defaults to [`Endianness::Little`].
+        #[builder(default)] endianness: Endianness,
+        /// Subtract the midpoint (127.5 for `U8`, 32767.5 for `U16`) from unsigned samples so they are centered around zero.
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0] by dividing by the full-scale value of the sample type.
+        /// Float sample types are passed through unchanged.
+        #[builder(default)] normalize: bool,
+        /// Normalize by dividing by this value instead of the full-scale value of the sample type, implies `normalize`.
+        /// Unlike `normalize`, this also applies to float sample types. Must be positive and finite.
+        normalize_with: Option<f64>,
+        /// Shift integer components right by this many bits before the conversion, e.g. 4 to drop the noise bits
+        /// of 12-bit samples left-justified in 16-bit words. The shift is arithmetic for signed types.
+        /// Must be less than the number of bits of a component. Defaults to 0.
+        #[builder(default)] bit_shift: u8,
+        /// Interpret signed integer samples as offset binary instead of two's complement, as output by some ADCs:
+        /// the most significant bit of each component is flipped before the conversion. Applied before `bit_shift`.
+        #[builder(default)] offset_binary: bool,
+        /// Swap the I and Q components of each sample, which mirrors the spectrum.
+        /// Useful for hardware or recordings that store Q before I.
+        #[builder(default)] swap_iq: bool,
+        /// Negate the imaginary part of each sample, which mirrors the spectrum. Applied after `swap_iq`.
+        #[builder(default)] conjugate: bool,
+    ) -> Result<Self, SdrReaderError> {
+        if samples_per_chunk == 0 {
+            return Err(SdrReaderError::InvalidConfig("samples_per_chunk must not be 0".to_string()));
+        }
+        Ok(AsyncSdrFileReader {
+            reader,
+            samples_per_chunk,
+            decode: DecodeOptions {
+                sample_type,
+                endianness,
+                center_unsigned,
+                normalize: normalize || normalize_with.is_some(),
+                swap_iq,
+                conjugate,
+                full_scale: normalize_with,
+                bit_shift,
+                offset_binary,
+                byte_table: None,
+            }
+            .validate()?
+            .with_byte_table(),
+            buffer: Vec::new(),
+        })
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncSdrFileReader<R> {
+    /// Read the raw bytes of the next chunk into the internal buffer.
+    /// Returns `false` if no complete chunk is left.
+    async fn read_chunk_bytes(&mut self) -> Result<bool, std::io::Error> {
+        self.buffer.resize(self.samples_per_chunk * self.decode.sample_type.sample_len(), 0);
+        match self.reader.read_exact(&mut self.buffer).await {
+            Ok(_) => Ok(true),
+            Err(why) => match why.kind() {
+                ErrorKind::UnexpectedEof => Ok(false),
+                _ => Err(why),
+            },
+        }
+    }
+
+    /// Read the next chunk of samples as Complex<f32>.
+    ///
+    /// # Warning
+    /// If you have set the sample type to `SampleType::F64`, you should use `read_next_chunk_complexf64` instead. Otherwise, the values will be truncated and you will lose accuracy.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the stream is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the stream other than reaching the end
+    pub async fn read_next_chunk_complexf32(&mut self) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        if !self.read_chunk_bytes().await? {
+            return Ok(None);
+        }
+        let mut samples = Vec::with_capacity(self.samples_per_chunk);
+        self.decode.decode(&self.buffer, &mut samples);
+        Ok(Some(samples))
+    }
+
+    /// Read the next chunk of samples as Complex<f64>.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the stream is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the stream other than reaching the end
+    pub async fn read_next_chunk_complexf64(&mut self) -> Result<Option<Vec<Complex<f64>>>, SdrReaderError> {
+        if !self.read_chunk_bytes().await? {
+            return Ok(None);
+        }
+        let mut samples = Vec::with_capacity(self.samples_per_chunk);
+        self.decode.decode(&self.buffer, &mut samples);
+        Ok(Some(samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[tokio::test]
+    async fn test_async_reader_cursor() {
+        let mut bytes = Vec::new();
+        for value in [1i16, -2, 300, -400, 5000, -6000] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        let mut reader = AsyncSdrFileReader::from_reader(Cursor::new(bytes), 2, SampleType::I16)
+            .endianness(Endianness::Big)
+            .build()
+            .expect("Failed to create AsyncSdrFileReader");
+        assert_eq!(
+            reader.read_next_chunk_complexf32().await.unwrap().unwrap(),
+            vec![Complex::new(1.0, -2.0), Complex::new(300.0, -400.0)]
+        );
+        // Only one sample is left, which doesn't make up a whole chunk
+        assert_eq!(reader.read_next_chunk_complexf64().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_async_reader_matches_sync_reader() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut sync_reader = crate::SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let bytes = std::fs::read(file_path).unwrap();
+        let mut async_reader = AsyncSdrFileReader::from_reader(Cursor::new(bytes), 1024, SampleType::F32).build().unwrap();
+        while let Some(samples) = async_reader.read_next_chunk_complexf32().await.unwrap() {
+            assert_eq!(Some(samples), sync_reader.read_next_chunk_complexf32().unwrap());
+        }
+        assert_eq!(sync_reader.read_next_chunk_complexf32().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_async_reader_decode_options() {
+        let bytes = [1i16, -2, 300, -400, 5000, -6000, 7, 8].iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        let mut sync_reader = crate::SdrFileReader::from_reader(Cursor::new(bytes.clone()), 2, SampleType::I16)
+            .swap_iq(true)
+            .conjugate(true)
+            .normalize_with(2048.0)
+            .bit_shift(4)
+            .offset_binary(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut async_reader = AsyncSdrFileReader::from_reader(Cursor::new(bytes), 2, SampleType::I16)
+            .swap_iq(true)
+            .conjugate(true)
+            .normalize_with(2048.0)
+            .bit_shift(4)
+            .offset_binary(true)
+            .build()
+            .expect("Failed to create AsyncSdrFileReader");
+        while let Some(samples) = async_reader.read_next_chunk_complexf32().await.unwrap() {
+            assert_eq!(Some(samples), sync_reader.read_next_chunk_complexf32().unwrap());
+        }
+        assert_eq!(sync_reader.read_next_chunk_complexf32().unwrap(), None);
+
+        let result = AsyncSdrFileReader::from_reader(Cursor::new(Vec::new()), 2, SampleType::I16).normalize_with(0.0).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,119 @@
+//! A reusable configuration for opening several files with the same settings.
+
+use std::path::Path;
+use crate::{Endianness, SampleType, SdrFileReader, SdrReaderError, TrailingPolicy};
+
+/// The settings of an [`SdrFileReader`] that describe the format of the samples, to open several files with
+/// [`SdrFileReader::open`] without repeating the builder calls. The fields have the same meaning as the builder options
+/// of the same name. For the options not covered here, use [`SdrFileReader::builder`] instead.
+///
+/// # Example
+/// ```
+/// use sdr_iq_file_reader::{SdrFileReader, SdrReaderConfig, SampleType};
+/// let config = SdrReaderConfig { normalize: true, ..SdrReaderConfig::new(1024, SampleType::F32) };
+/// let mut reader = SdrFileReader::open("gqrx_20240929_015218_580206500_2400000_fc.raw", &config)
+///     .expect("Failed to create SdrFileReader");
+/// let samples = reader.read_next_chunk_complexf32().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct SdrReaderConfig {
+    /// The number of samples in each chunk
+    pub samples_per_chunk: usize,
+    /// The type of the samples
+    pub sample_type: SampleType,
+    /// The byte order of multi-byte samples
+    pub endianness: Endianness,
+    /// Subtract the midpoint from unsigned samples so they are centered around zero
+    pub center_unsigned: bool,
+    /// Scale integer samples to the range [-1.0, 1.0]
+    pub normalize: bool,
+    /// Normalize by dividing by this value instead of the full-scale value of the sample type
+    pub normalize_with: Option<f64>,
+    /// Shift integer components right by this many bits before the conversion
+    pub bit_shift: u8,
+    /// Interpret signed integer samples as offset binary instead of two's complement
+    pub offset_binary: bool,
+    /// What happens to the bytes at the end of the file that don't make up a whole chunk
+    pub trailing_bytes: TrailingPolicy,
+    /// Swap the I and Q components of each sample
+    pub swap_iq: bool,
+    /// Negate the imaginary part of each sample
+    pub conjugate: bool,
+    /// Subtract a running estimate of the DC offset from each sample
+    pub remove_dc: bool,
+}
+
+impl SdrReaderConfig {
+    /// A configuration for chunks of `samples_per_chunk` samples of `sample_type`,
+    /// with the defaults of the builder for all other settings.
+    #[must_use]
+    pub fn new(samples_per_chunk: usize, sample_type: SampleType) -> Self {
+        SdrReaderConfig {
+            samples_per_chunk,
+            sample_type,
+            endianness: Endianness::default(),
+            center_unsigned: false,
+            normalize: false,
+            normalize_with: None,
+            bit_shift: 0,
+            offset_binary: false,
+            trailing_bytes: TrailingPolicy::default(),
+            swap_iq: false,
+            conjugate: false,
+            remove_dc: false,
+        }
+    }
+}
+
+impl SdrFileReader {
+    /// Open the file at `file_path` with the settings of `config`, like [`SdrFileReader::builder`] with the same options.
+    /// Metadata is parsed from gqrx file names, and files with a `.gz` extension are decompressed.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Open` if the file could not be opened
+    /// - `SdrReaderError::InvalidConfig` if the settings are invalid, see [`SdrFileReader::from_reader`]
+    pub fn open(file_path: impl AsRef<Path>, config: &SdrReaderConfig) -> Result<Self, SdrReaderError> {
+        SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(config.samples_per_chunk)
+            .sample_type(config.sample_type)
+            .endianness(config.endianness.clone())
+            .center_unsigned(config.center_unsigned)
+            .normalize(config.normalize)
+            .maybe_normalize_with(config.normalize_with)
+            .bit_shift(config.bit_shift)
+            .offset_binary(config.offset_binary)
+            .trailing_bytes(config.trailing_bytes)
+            .swap_iq(config.swap_iq)
+            .conjugate(config.conjugate)
+            .remove_dc(config.remove_dc)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex;
+
+    #[test]
+    fn test_open_with_config() {
+        let config = SdrReaderConfig { swap_iq: true, ..SdrReaderConfig::new(2, SampleType::I16) };
+        let dir = std::env::temp_dir();
+        let paths = ["a", "b"].map(|name| dir.join(format!("sdr_iq_file_reader_config_{}_{name}.raw", std::process::id())));
+        for (offset, path) in (0..).step_by(4).zip(&paths) {
+            std::fs::write(path, (offset..offset + 4i16).flat_map(i16::to_le_bytes).collect::<Vec<_>>()).unwrap();
+        }
+
+        let mut first = SdrFileReader::open(&paths[0], &config).unwrap();
+        assert_eq!(first.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(1.0, 0.0), Complex::new(3.0, 2.0)]);
+        let mut second = SdrFileReader::open(&paths[1], &config).unwrap();
+        assert_eq!(second.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(5.0, 4.0), Complex::new(7.0, 6.0)]);
+        assert_eq!(second.samples_per_chunk(), 2);
+        drop((first, second));
+        for path in &paths {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
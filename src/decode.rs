@@ -0,0 +1,353 @@
+//! Conversion of raw sample bytes into complex samples.
+
+use alloc::vec::Vec;
+use num_complex::Complex;
+use num_traits::{Float, FromPrimitive};
+use crate::{Endianness, SampleType};
+#[cfg(feature = "std")]
+use crate::SdrReaderError;
+
+/// The settings that determine how raw bytes are turned into samples.
+#[cfg(feature = "std")]
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct DecodeOptions {
+    pub(crate) sample_type: SampleType,
+    pub(crate) endianness: Endianness,
+    pub(crate) center_unsigned: bool,
+    pub(crate) normalize: bool,
+    /// Put the second component of each sample in `re` and the first in `im`
+    pub(crate) swap_iq: bool,
+    /// Negate the imaginary part of each sample (after swapping)
+    pub(crate) conjugate: bool,
+    /// A custom divisor for normalization that replaces the full-scale value of the sample type
+    pub(crate) full_scale: Option<f64>,
+    /// The number of bits integer components are shifted right by before the conversion
+    pub(crate) bit_shift: u8,
+    /// Flip the most significant bit of signed integer components, to convert offset binary to two's complement
+    pub(crate) offset_binary: bool,
+    /// The decoded value of each byte of `U8` and `I8` components with centering or normalization,
+    /// set by [`DecodeOptions::with_byte_table`]
+    pub(crate) byte_table: Option<Box<[f64; 256]>>,
+}
+
+#[cfg(feature = "std")]
+impl DecodeOptions {
+    /// The value integer samples are divided by when normalization is enabled.
+    /// Returns `None` if no scaling should be applied.
+    pub(crate) fn full_scale(&self) -> Option<f64> {
+        if !self.normalize {
+            return None;
+        }
+        if let Some(full_scale) = self.full_scale {
+            return Some(full_scale);
+        }
+        match self.sample_type {
+            SampleType::U8 if self.center_unsigned => Some(127.5),
+            SampleType::U8 => Some(255.0),
+            SampleType::I8 => Some(128.0),
+            SampleType::U16 if self.center_unsigned => Some(32767.5),
+            SampleType::U16 => Some(65535.0),
+            SampleType::I16 => Some(32768.0),
+            SampleType::I24 => Some(8_388_608.0),
+            SampleType::F16 | SampleType::F32 | SampleType::F64 => None,
+        }
+    }
+
+    /// The value subtracted from each component when centering unsigned samples.
+    pub(crate) fn center_offset(&self) -> f64 {
+        match self.sample_type {
+            SampleType::U8 if self.center_unsigned => 127.5,
+            SampleType::U16 if self.center_unsigned => 32767.5,
+            _ => 0.0,
+        }
+    }
+
+    /// Check the options that can be out of range, shared by all readers.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `full_scale` is not positive and finite
+    /// - `SdrReaderError::InvalidConfig` if `bit_shift` is not less than the number of bits of a component
+    /// - `SdrReaderError::InvalidConfig` if the sample type is `F16` and the `half` feature is disabled
+    pub(crate) fn validate(self) -> Result<Self, SdrReaderError> {
+        if self.full_scale.is_some_and(|full_scale| !(full_scale.is_finite() && full_scale > 0.0)) {
+            return Err(SdrReaderError::InvalidConfig("normalize_with must be positive and finite".to_string()));
+        }
+        if usize::from(self.bit_shift) >= self.sample_type.sample_len() * 4 {
+            return Err(SdrReaderError::InvalidConfig("bit_shift must be less than the number of bits of a component".to_string()));
+        }
+        if !cfg!(feature = "half") && self.sample_type == SampleType::F16 {
+            return Err(SdrReaderError::InvalidConfig("Decoding F16 samples requires the half feature".to_string()));
+        }
+        Ok(self)
+    }
+
+    /// Precompute the decoded value of each byte for `U8` and `I8` samples that are centered or normalized,
+    /// which replaces the arithmetic per component with a table lookup.
+    /// The table is filled by decoding each byte with the same arithmetic, so the samples are exactly the same.
+    pub(crate) fn with_byte_table(mut self) -> Self {
+        self.byte_table = None;
+        if matches!(self.sample_type, SampleType::U8 | SampleType::I8) && (self.center_unsigned || self.normalize) {
+            let bytes = (0..=u8::MAX).flat_map(|byte| [byte, 0]).collect::<Vec<_>>();
+            let mut table = Box::new([0.0; 256]);
+            let mut entries = table.iter_mut();
+            self.decode_arithmetic(&bytes, |value: f64, _| {
+                if let Some(entry) = entries.next() {
+                    *entry = value;
+                }
+            });
+            self.byte_table = Some(table);
+        }
+        self
+    }
+
+    /// Decode `buffer`, which must only contain whole samples, and append the samples to `samples`.
+    pub(crate) fn decode<T: Float + FromPrimitive>(&self, buffer: &[u8], samples: &mut Vec<Complex<T>>) {
+        samples.reserve(buffer.len() / self.sample_type.sample_len());
+        self.decode_with(buffer, |i, q| samples.push(Complex::new(i, q)));
+    }
+
+    /// Decode `buffer`, which must only contain whole samples, and pass the I and Q components of each sample to `sink`.
+    pub(crate) fn decode_with<T: Float + FromPrimitive>(&self, buffer: &[u8], mut sink: impl FnMut(T, T)) {
+        let (swap_iq, conjugate) = (self.swap_iq, self.conjugate);
+        let sink = |i, q| {
+            let (re, im) = if swap_iq { (q, i) } else { (i, q) };
+            sink(re, if conjugate { -im } else { im });
+        };
+        match &self.byte_table {
+            Some(table) => for_each_sample(buffer, sink, |[byte]: [u8; 1]| T::from_f64(table[usize::from(byte)]).unwrap_or_else(T::nan)),
+            None => self.decode_arithmetic(buffer, sink),
+        }
+    }
+
+    /// Decode `buffer` like [`DecodeOptions::decode_with`], but without the byte table and the I/Q options.
+    fn decode_arithmetic<T: Float + FromPrimitive>(&self, buffer: &[u8], sink: impl FnMut(T, T)) {
+        let full_scale = self.full_scale().unwrap_or(1.0);
+        let bits = IntBits { shift: self.bit_shift, offset_binary: self.offset_binary };
+        decode_scaled(buffer, self.sample_type, &self.endianness, bits, self.center_offset(), full_scale, sink);
+    }
+}
+
+/// Decode raw bytes into complex samples, without any centering or normalization.
+/// Trailing bytes that don't make up a whole sample are ignored.
+/// Without the `half` feature, `F16` samples decode to an empty `Vec`.
+///
+/// # Example
+/// ```
+/// use num_complex::Complex;
+/// use sdr_iq_file_reader::{decode_into, Endianness, SampleType};
+/// let samples = decode_into::<f32>(&[0x01, 0x00, 0xFF, 0xFF], &SampleType::I16, &Endianness::Little);
+/// assert_eq!(samples, vec![Complex::new(1.0, -1.0)]);
+/// ```
+#[must_use]
+pub fn decode_into<T: Float + FromPrimitive>(buffer: &[u8], sample_type: &SampleType, endianness: &Endianness) -> Vec<Complex<T>> {
+    let mut samples = Vec::with_capacity(buffer.len() / sample_type.sample_len());
+    decode_scaled(buffer, *sample_type, endianness, IntBits::default(), 0.0, 1.0, |i, q| samples.push(Complex::new(i, q)));
+    samples
+}
+
+/// How the bits of integer components are adjusted before the conversion.
+#[derive(Clone, Copy, Default)]
+struct IntBits {
+    /// The number of bits to shift right by
+    shift: u8,
+    /// Flip the most significant bit of signed components before the shift
+    offset_binary: bool,
+}
+
+impl IntBits {
+    /// The sign bit of signed components of `bits` bits if they are offset binary, otherwise 0.
+    fn sign_flip(self, bits: u32) -> i32 {
+        if self.offset_binary { 1 << (bits - 1) } else { 0 }
+    }
+}
+
+/// Decode each component, adjust the bits of integers, subtract `offset`, divide by `full_scale`
+/// and pass the I and Q components to `sink`.
+fn decode_scaled<T: Float + FromPrimitive>(
+    buffer: &[u8],
+    sample_type: SampleType,
+    endianness: &Endianness,
+    bits: IntBits,
+    offset: f64,
+    full_scale: f64,
+    sink: impl FnMut(T, T),
+) {
+    let convert = |value: f64| T::from_f64((value - offset) / full_scale).unwrap_or_else(T::nan);
+    let shift = bits.shift;
+    // Flipping the sign bit of the sign-extended value and sign-extending again from the flipped bit
+    let signed = |value: i32, width: u32| (((value ^ bits.sign_flip(width)) << (32 - width)) >> (32 - width)) >> shift;
+    match sample_type {
+        SampleType::U8 => for_each_sample(buffer, sink, |[byte]: [u8; 1]| convert(f64::from(byte >> shift))),
+        SampleType::I8 => for_each_sample(buffer, sink, |bytes| convert(f64::from(signed(i8::from_ne_bytes(bytes).into(), 8)))),
+        SampleType::U16 => for_each_sample(buffer, sink, |bytes| convert(f64::from(endianness.u16_from_bytes(bytes) >> shift))),
+        SampleType::I16 => for_each_sample(buffer, sink, |bytes| convert(f64::from(signed(endianness.i16_from_bytes(bytes).into(), 16)))),
+        SampleType::I24 => for_each_sample(buffer, sink, |bytes| convert(f64::from(signed(endianness.i24_from_bytes(bytes), 24)))),
+        #[cfg(feature = "half")]
+        SampleType::F16 => for_each_sample(buffer, sink, |bytes| convert(f64::from(endianness.f16_from_bytes(bytes)))),
+        // Rejected by `DecodeOptions::validate`, so only `decode_into` gets here
+        #[cfg(not(feature = "half"))]
+        SampleType::F16 => {}
+        SampleType::F32 => for_each_sample(buffer, sink, |bytes| convert(f64::from(endianness.f32_from_bytes(bytes)))),
+        SampleType::F64 => for_each_sample(buffer, sink, |bytes| convert(endianness.f64_from_bytes(bytes))),
+    }
+}
+
+/// Split `buffer` into samples of two components with `N` bytes each and decode them as I and Q.
+/// The component length is taken from the array type `decode` expects, so it always matches the decoded type.
+fn for_each_sample<const N: usize, T>(buffer: &[u8], mut sink: impl FnMut(T, T), decode: impl Fn([u8; N]) -> T) {
+    for s in buffer.chunks_exact(2 * N) {
+        let (i, q) = s.split_at(N);
+        // Both halves are exactly N bytes long, so the conversions can't fail
+        sink(decode(i.try_into().unwrap()), decode(q.try_into().unwrap()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_each_sample_type() {
+        assert_eq!(decode_into::<f32>(&[0, 255, 128, 1], &SampleType::U8, &Endianness::Little), vec![Complex::new(0.0, 255.0), Complex::new(128.0, 1.0)]);
+        assert_eq!(decode_into::<f32>(&[0x80, 0x7F], &SampleType::I8, &Endianness::Little), vec![Complex::new(-128.0, 127.0)]);
+        assert_eq!(decode_into::<f32>(&[0x34, 0x12, 0xFF, 0xFF], &SampleType::U16, &Endianness::Little), vec![Complex::new(4660.0, 65535.0)]);
+        assert_eq!(decode_into::<f32>(&[0x12, 0x34, 0x80, 0x00], &SampleType::U16, &Endianness::Big), vec![Complex::new(4660.0, 32768.0)]);
+        assert_eq!(decode_into::<f32>(&[0xFF, 0xFF, 0x00, 0x80], &SampleType::I16, &Endianness::Little), vec![Complex::new(-1.0, -32768.0)]);
+        assert_eq!(decode_into::<f64>(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F], &SampleType::I24, &Endianness::Little), vec![Complex::new(-1.0, 8_388_607.0)]);
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&0.125f32.to_le_bytes());
+        bytes.extend_from_slice(&(-3.5f32).to_le_bytes());
+        assert_eq!(decode_into::<f64>(&bytes, &SampleType::F32, &Endianness::Little), vec![Complex::new(0.125, -3.5)]);
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&1e-300f64.to_be_bytes());
+        bytes.extend_from_slice(&(-2.0f64).to_be_bytes());
+        assert_eq!(decode_into::<f64>(&bytes, &SampleType::F64, &Endianness::Big), vec![Complex::new(1e-300, -2.0)]);
+    }
+
+    #[test]
+    fn test_decode_f64_bit_pattern() {
+        // 1.5 and -0.1 as IEEE 754 doubles, little-endian
+        let bytes = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0x3F, 0x9A, 0x99, 0x99, 0x99, 0x99, 0x99, 0xB9, 0xBF];
+        let samples = decode_into::<f64>(&bytes, &SampleType::F64, &Endianness::Little);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].re.to_bits(), 1.5f64.to_bits());
+        assert_eq!(samples[0].im.to_bits(), (-0.1f64).to_bits());
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_decode_f16() {
+        // 1.5 and -0.25 as IEEE 754 half-precision floats
+        let samples = decode_into::<f32>(&[0x00, 0x3E, 0x00, 0xB4], &SampleType::F16, &Endianness::Little);
+        assert_eq!(samples, vec![Complex::new(1.5, -0.25)]);
+        let samples = decode_into::<f64>(&[0x3E, 0x00, 0xB4, 0x00], &SampleType::F16, &Endianness::Big);
+        assert_eq!(samples, vec![Complex::new(1.5, -0.25)]);
+        assert_eq!(SampleType::F16.sample_len(), 4);
+    }
+
+    #[test]
+    fn test_decode_ignores_trailing_bytes() {
+        assert_eq!(decode_into::<f32>(&[1, 2, 3], &SampleType::U8, &Endianness::Little), vec![Complex::new(1.0, 2.0)]);
+    }
+
+    // Only built without the `std` feature, where the crate is `no_std` and nothing but the decoding is available
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn test_decode_slice_without_std() {
+        const CAPTURE: [u8; 8] = [0x00, 0x40, 0x00, 0xC0, 0xFF, 0x7F, 0x01, 0x80];
+        let samples: Vec<Complex<f32>> = decode_into(&CAPTURE, &SampleType::I16, &Endianness::Little);
+        assert_eq!(samples, [Complex::new(16384.0, -16384.0), Complex::new(32767.0, -32767.0)]);
+        assert_eq!(SampleType::I16.to_string(), "i16");
+    }
+
+    #[cfg(all(feature = "std", not(feature = "half")))]
+    #[test]
+    fn test_f16_requires_half() {
+        let options = DecodeOptions {
+            sample_type: SampleType::F16,
+            endianness: Endianness::Little,
+            center_unsigned: true,
+            normalize: true,
+            swap_iq: false,
+            conjugate: false,
+            full_scale: None,
+            bit_shift: 0,
+            offset_binary: false,
+            byte_table: None,
+        };
+        assert!(matches!(options.validate(), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(decode_into::<f32>(&[0x00, 0x3E, 0x00, 0xB4], &SampleType::F16, &Endianness::Little).is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_decode_options_append() {
+        let options = DecodeOptions {
+            sample_type: SampleType::U8,
+            endianness: Endianness::Little,
+            center_unsigned: true,
+            normalize: true,
+            swap_iq: false,
+            conjugate: false,
+            full_scale: None,
+            bit_shift: 0,
+            offset_binary: false,
+            byte_table: None,
+        };
+        let mut samples = vec![Complex::new(5.0f32, 5.0)];
+        options.decode(&[255, 0], &mut samples);
+        assert_eq!(samples, vec![Complex::new(5.0, 5.0), Complex::new(1.0, -1.0)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_byte_table_matches_arithmetic() {
+        let bytes = (0..=u8::MAX).flat_map(|byte| [byte, byte.wrapping_mul(37)]).collect::<Vec<_>>();
+        for sample_type in [SampleType::U8, SampleType::I8] {
+            for (center_unsigned, normalize, full_scale, bit_shift, offset_binary) in [
+                (true, false, None, 0, false),
+                (false, true, None, 0, false),
+                (true, true, None, 0, false),
+                (true, true, Some(100.0), 2, false),
+                (false, true, None, 1, true),
+            ] {
+                let options = DecodeOptions {
+                    sample_type,
+                    endianness: Endianness::Little,
+                    center_unsigned,
+                    normalize,
+                    swap_iq: false,
+                    conjugate: true,
+                    full_scale,
+                    bit_shift,
+                    offset_binary,
+                    byte_table: None,
+                };
+                let (mut arithmetic, mut lookup) = (Vec::<Complex<f32>>::new(), Vec::<Complex<f32>>::new());
+                options.decode(&bytes, &mut arithmetic);
+                let options = options.with_byte_table();
+                assert!(options.byte_table.is_some());
+                options.decode(&bytes, &mut lookup);
+                let to_bits = |samples: &[Complex<f32>]| samples.iter().map(|s| (s.re.to_bits(), s.im.to_bits())).collect::<Vec<_>>();
+                assert_eq!(to_bits(&lookup), to_bits(&arithmetic), "{sample_type} {center_unsigned} {normalize} {full_scale:?}");
+            }
+        }
+
+        // Without centering or normalization, and for wider samples, the arithmetic is used
+        let options = DecodeOptions {
+            sample_type: SampleType::U8,
+            endianness: Endianness::Little,
+            center_unsigned: false,
+            normalize: false,
+            swap_iq: false,
+            conjugate: false,
+            full_scale: None,
+            bit_shift: 0,
+            offset_binary: false,
+            byte_table: None,
+        };
+        assert!(options.with_byte_table().byte_table.is_none());
+    }
+}
@@ -0,0 +1,41 @@
+//! Hashing the raw bytes a reader consumes.
+
+use std::io::{Read, Write};
+use crc32fast::Hasher;
+
+/// A [`Write`] sink that discards the bytes written to it, after feeding them to a hasher if there is one.
+struct HashingSink<'a>(Option<&'a mut Hasher>);
+
+impl Write for HashingSink<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(hasher) = &mut self.0 {
+            hasher.update(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Read and discard up to `len` bytes from `reader`, feeding them to `hasher` if there is one.
+/// Returns the number of bytes read, which is less than `len` if the reader ended.
+pub(crate) fn discard(reader: &mut impl Read, len: u64, hasher: Option<&mut Hasher>) -> std::io::Result<u64> {
+    std::io::copy(&mut reader.take(len), &mut HashingSink(hasher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discard_hashes_the_bytes() {
+        let mut hasher = Hasher::new();
+        let mut reader: &[u8] = b"123456789 and more";
+        assert_eq!(discard(&mut reader, 9, Some(&mut hasher)).unwrap(), 9);
+        // The standard check value of CRC-32
+        assert_eq!(hasher.finalize(), 0xCBF4_3926);
+        assert_eq!(discard(&mut reader, 100, None).unwrap(), 9);
+    }
+}
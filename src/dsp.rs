@@ -0,0 +1,107 @@
+//! Signal processing steps applied to the decoded samples.
+
+use std::collections::VecDeque;
+use std::f64::consts::TAU;
+use num_complex::Complex;
+use num_traits::{Float, FromPrimitive};
+
+/// Multiply each sample by the phasor `e^{j phase}`, advancing `phase` by `step` radians per sample.
+/// Returns the phase for the sample after the last one, so the next chunk continues without a discontinuity.
+pub(crate) fn mix<T: Float + FromPrimitive>(samples: &mut [Complex<T>], mut phase: f64, step: f64) -> f64 {
+    for sample in samples {
+        let (sin, cos) = phase.sin_cos();
+        let phasor = Complex::new(T::from_f64(cos).unwrap_or_else(T::nan), T::from_f64(sin).unwrap_or_else(T::nan));
+        *sample = *sample * phasor;
+        // Keep the phase small so it doesn't lose precision over long recordings
+        phase = (phase + step).rem_euclid(TAU);
+    }
+    phase
+}
+
+/// Multiply each sample by `gain`, which scales and rotates it.
+pub(crate) fn scale<T: Float + FromPrimitive>(samples: &mut [Complex<T>], gain: Complex<f64>) {
+    let gain = Complex::new(T::from_f64(gain.re).unwrap_or_else(T::nan), T::from_f64(gain.im).unwrap_or_else(T::nan));
+    for sample in samples {
+        *sample = *sample * gain;
+    }
+}
+
+/// The product of a real and a complex gain, or `None` if neither is set.
+pub(crate) fn combine_gains(gain: Option<f32>, complex_gain: Option<Complex<f32>>) -> Option<Complex<f64>> {
+    if gain.is_none() && complex_gain.is_none() {
+        return None;
+    }
+    let complex_gain = complex_gain.unwrap_or(Complex::new(1.0, 0.0));
+    Some(Complex::new(f64::from(complex_gain.re), f64::from(complex_gain.im)) * f64::from(gain.unwrap_or(1.0)))
+}
+
+/// Subtract a running estimate of the DC offset from each sample.
+/// The estimate is a single-pole IIR low-pass per component, `dc += alpha * (x - dc)`, and is updated in place
+/// so it keeps converging across chunks.
+pub(crate) fn remove_dc<T: Float + FromPrimitive>(samples: &mut [Complex<T>], estimate: &mut Complex<f64>, alpha: f64) {
+    for sample in samples {
+        let value = Complex::new(sample.re.to_f64().unwrap_or(f64::NAN), sample.im.to_f64().unwrap_or(f64::NAN));
+        *estimate += (value - *estimate) * alpha;
+        let corrected = value - *estimate;
+        *sample = Complex::new(T::from_f64(corrected.re).unwrap_or_else(T::nan), T::from_f64(corrected.im).unwrap_or_else(T::nan));
+    }
+}
+
+/// A moving average over the last `window` values, which carries over between chunks.
+/// Until `window` values have been seen, the average is over the values seen so far.
+#[derive(Clone)]
+pub(crate) struct MovingAverage {
+    window: usize,
+    /// The last `window` values, oldest first
+    history: VecDeque<f32>,
+    sum: f64,
+}
+
+impl MovingAverage {
+    /// Create a moving average over `window` values, which must not be 0.
+    pub(crate) fn new(window: usize) -> Self {
+        MovingAverage { window, history: VecDeque::with_capacity(window), sum: 0.0 }
+    }
+
+    /// The number of values averaged.
+    pub(crate) fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Forget the values seen so far.
+    pub(crate) fn reset(&mut self) {
+        self.history.clear();
+        self.sum = 0.0;
+    }
+
+    /// Replace each value by the average of it and the `window - 1` values before it.
+    pub(crate) fn process(&mut self, values: &mut [f32]) {
+        for value in values {
+            if self.history.len() == self.window {
+                self.sum -= f64::from(self.history.pop_front().unwrap_or(0.0));
+            }
+            self.history.push_back(*value);
+            self.sum += f64::from(*value);
+            #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+            let average = (self.sum / self.history.len() as f64) as f32;
+            *value = average;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_mix_carries_phase() {
+        let mut samples = vec![Complex::new(1.0, 0.0); 4];
+        let phase = mix(&mut samples, 0.0, FRAC_PI_2);
+        assert!((phase - 0.0).abs() < 1e-12);
+        let expected = [Complex::new(1.0, 0.0), Complex::new(0.0, 1.0), Complex::new(-1.0, 0.0), Complex::new(0.0, -1.0)];
+        for (sample, expected) in samples.iter().zip(expected) {
+            assert!((sample - expected).norm() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! The error type returned by the readers.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// An error that occurred while reading samples or the metadata describing them.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SdrReaderError {
+    /// An I/O error from the underlying reader
+    Io(std::io::Error),
+    /// The file at `path` could not be opened
+    Open {
+        /// The path of the file
+        path: PathBuf,
+        /// The error returned when opening it
+        source: std::io::Error,
+    },
+    /// The data ended in the middle of a sample, leaving `bytes_left` bytes that don't make up a whole sample
+    UnexpectedPartialSample {
+        /// The number of trailing bytes that were not decoded
+        bytes_left: usize,
+    },
+    /// The data ended in the middle of a chunk with `TrailingPolicy::Error`, leaving `bytes_left` bytes that were not decoded
+    UnexpectedPartialChunk {
+        /// The number of trailing bytes that were not decoded
+        bytes_left: usize,
+    },
+    /// The metadata (WAV header, `SigMF` metadata, ...) is malformed or describes an unsupported format
+    InvalidMetadata(String),
+    /// The reader was configured with options that can't be used together
+    InvalidConfig(String),
+    /// A requested position lies past the end of the samples
+    OutOfRange(String),
+}
+
+impl fmt::Display for SdrReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SdrReaderError::Io(why) => write!(f, "I/O error: {why}"),
+            SdrReaderError::Open { path, source } => write!(f, "Failed to open {}: {source}", path.display()),
+            SdrReaderError::UnexpectedPartialSample { bytes_left } => {
+                write!(f, "Data ended in the middle of a sample, {bytes_left} trailing bytes left")
+            }
+            SdrReaderError::UnexpectedPartialChunk { bytes_left } => {
+                write!(f, "Data ended in the middle of a chunk, {bytes_left} trailing bytes left")
+            }
+            SdrReaderError::InvalidMetadata(message) => write!(f, "Invalid metadata: {message}"),
+            SdrReaderError::InvalidConfig(message) => write!(f, "Invalid configuration: {message}"),
+            SdrReaderError::OutOfRange(message) => write!(f, "Out of range: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SdrReaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SdrReaderError::Io(why) | SdrReaderError::Open { source: why, .. } => Some(why),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SdrReaderError {
+    fn from(why: std::io::Error) -> Self {
+        SdrReaderError::Io(why)
+    }
+}
+
+impl From<SdrReaderError> for std::io::Error {
+    /// Unwrap I/O errors, keep the kind of errors opening a file, and wrap the other errors into an error of kind `InvalidData`.
+    fn from(why: SdrReaderError) -> Self {
+        match why {
+            SdrReaderError::Io(why) => why,
+            SdrReaderError::Open { ref source, .. } => std::io::Error::new(source.kind(), why),
+            why => std::io::Error::new(std::io::ErrorKind::InvalidData, why),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_from_io_error() {
+        let error = SdrReaderError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing"));
+        assert!(matches!(&error, SdrReaderError::Io(why) if why.kind() == std::io::ErrorKind::NotFound));
+        assert!(error.source().is_some());
+        assert_eq!(error.to_string(), "I/O error: missing");
+        assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            SdrReaderError::UnexpectedPartialSample { bytes_left: 3 }.to_string(),
+            "Data ended in the middle of a sample, 3 trailing bytes left"
+        );
+        assert_eq!(
+            SdrReaderError::UnexpectedPartialChunk { bytes_left: 9 }.to_string(),
+            "Data ended in the middle of a chunk, 9 trailing bytes left"
+        );
+        assert_eq!(SdrReaderError::InvalidMetadata("bad".to_string()).to_string(), "Invalid metadata: bad");
+        let error = SdrReaderError::Open {
+            path: PathBuf::from("capture.raw"),
+            source: std::io::Error::new(std::io::ErrorKind::NotFound, "missing"),
+        };
+        assert_eq!(error.to_string(), "Failed to open capture.raw: missing");
+        assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,102 @@
+//! Streaming low-pass FIR filtering.
+
+use std::f64::consts::{PI, TAU};
+use num_complex::Complex;
+use num_traits::{Float, FromPrimitive};
+use crate::SdrReaderError;
+
+/// A low-pass FIR filter designed with the windowed-sinc method and a Hamming window.
+/// The last input samples are kept between calls, so chunked input is filtered exactly like one long input.
+#[derive(Clone)]
+pub(crate) struct LowPass {
+    /// The filter taps, normalized to a gain of 1 at DC
+    taps: Vec<f64>,
+    /// The last `taps.len() - 1` input samples, followed by the samples of the current chunk while filtering
+    history: Vec<Complex<f64>>,
+}
+
+impl LowPass {
+    /// Design a filter with `num_taps` taps and a cutoff of `cutoff` cycles per sample, between 0.0 and 0.5.
+    /// `num_taps` must be nonzero.
+    pub(crate) fn new(cutoff: f64, num_taps: usize) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let taps = (0..num_taps)
+            .map(|n| {
+                let t = n as f64 - (num_taps - 1) as f64 / 2.0;
+                let window = match num_taps {
+                    1 => 1.0,
+                    _ => 0.54 - 0.46 * (TAU * n as f64 / (num_taps - 1) as f64).cos(),
+                };
+                2.0 * cutoff * sinc(2.0 * cutoff * t) * window
+            })
+            .collect::<Vec<_>>();
+        let gain = taps.iter().sum::<f64>();
+        LowPass {
+            taps: taps.iter().map(|tap| tap / gain).collect(),
+            history: vec![Complex::new(0.0, 0.0); num_taps - 1],
+        }
+    }
+
+    /// Design the filter for the `lowpass` option of a reader, a cutoff in Hz and a number of taps.
+    pub(crate) fn from_options(lowpass: Option<(f64, usize)>, sample_rate_hz: Option<u64>, overlap: usize) -> Result<Option<Self>, SdrReaderError> {
+        match (lowpass, sample_rate_hz) {
+            (None, _) => Ok(None),
+            (Some(_), _) if overlap > 0 => Err(SdrReaderError::InvalidConfig("lowpass can't be combined with overlap".to_string())),
+            #[allow(clippy::cast_precision_loss)]
+            (Some((cutoff_hz, num_taps)), Some(rate_hz)) if rate_hz > 0 => {
+                let cutoff = cutoff_hz / rate_hz as f64;
+                if !(cutoff > 0.0 && cutoff < 0.5) || num_taps == 0 {
+                    return Err(SdrReaderError::InvalidConfig(
+                        "lowpass needs a cutoff between 0 and half the sample rate and at least one tap".to_string(),
+                    ));
+                }
+                Ok(Some(LowPass::new(cutoff, num_taps)))
+            }
+            (Some(_), _) => Err(SdrReaderError::InvalidConfig("lowpass requires a nonzero sample_rate_hz".to_string())),
+        }
+    }
+
+    /// Forget the previous input samples, as if the filter was just created.
+    pub(crate) fn reset(&mut self) {
+        self.history.fill(Complex::new(0.0, 0.0));
+    }
+
+    /// Filter a chunk in place. The output is delayed by `(num_taps - 1) / 2` samples.
+    pub(crate) fn process<T: Float + FromPrimitive>(&mut self, samples: &mut [Complex<T>]) {
+        let delay = self.taps.len() - 1;
+        self.history.extend(samples.iter().map(|sample| {
+            Complex::new(sample.re.to_f64().unwrap_or(f64::NAN), sample.im.to_f64().unwrap_or(f64::NAN))
+        }));
+        for (n, sample) in samples.iter_mut().enumerate() {
+            // `history[delay + n]` is input sample n, older samples come before it
+            let output = self.taps.iter().enumerate().map(|(j, tap)| self.history[delay + n - j] * tap).sum::<Complex<f64>>();
+            *sample = Complex::new(
+                T::from_f64(output.re).unwrap_or_else(T::nan),
+                T::from_f64(output.im).unwrap_or_else(T::nan),
+            );
+        }
+        let input_len = samples.len();
+        self.history.drain(..input_len);
+    }
+}
+
+/// The normalized sinc function `sin(πx) / (πx)`.
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_passes_dc() {
+        let mut filter = LowPass::new(0.1, 31);
+        let mut samples = vec![Complex::new(1.0, -0.5); 100];
+        filter.process(&mut samples);
+        // The first 30 outputs still see the zeros before the input
+        for sample in &samples[30..] {
+            assert!((sample - Complex::new(1.0, -0.5)).norm() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! The file an [`SdrFileReader`](crate::SdrFileReader) opened from a path reads from.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use bon::bon;
+use flate2::read::GzDecoder;
+use crate::{parse_gqrx_filename, CaptureMeta, Endianness, SampleType, SdrFileReader, SdrReaderError, TrailingPolicy};
+
+/// A capture file opened by path, either plain, gzip- or zstd-compressed, or a sequence of plain files read as one.
+/// Files with a planar layout are read as if their samples were interleaved.
+/// The file can be restricted to a byte range, which is then read as if it was the whole file.
+///
+/// Compressed files are decompressed on the fly while reading. They can't be seeked,
+/// so methods that need to seek, like [`SdrFileReader::total_samples`](crate::SdrFileReader::total_samples),
+/// return an error of kind `Unsupported` for them.
+pub struct InputFile {
+    kind: Kind,
+    window: Option<Window>,
+}
+
+enum Kind {
+    Plain(File),
+    Gzip(GzDecoder<File>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Decoder<'static, std::io::BufReader<File>>),
+    Concat(Concat),
+    Planar(Planar),
+}
+
+/// The byte range of the file that is read, positions are relative to its start.
+struct Window {
+    start: u64,
+    len: u64,
+    /// The position relative to `start`
+    position: u64,
+}
+
+/// Plain files read one after the other as a single stream.
+struct Concat {
+    files: Vec<File>,
+    /// The length of each file in bytes
+    lens: Vec<u64>,
+    /// The index of the file that is read next
+    current: usize,
+    /// The position in the combined stream
+    position: u64,
+}
+
+/// The number of samples [`Planar`] reads from the files at once.
+const PLANAR_BLOCK_SAMPLES: u64 = 4096;
+
+/// A file with all I components followed by all Q components, read as interleaved samples.
+struct Planar {
+    /// The file positioned in the I block
+    i: File,
+    /// The same file positioned in the Q block
+    q: File,
+    /// The length of a single component in bytes
+    component_len: usize,
+    total_samples: u64,
+    /// The index of the next sample whose components are read from the file
+    next_sample: u64,
+    /// The interleaved bytes of the last samples read from the file
+    interleaved: Vec<u8>,
+    /// The number of bytes of `interleaved` already returned
+    consumed: usize,
+}
+
+impl Planar {
+    /// Read the components of the next block of samples and interleave them.
+    fn fill(&mut self) -> std::io::Result<()> {
+        let count = usize::try_from((self.total_samples - self.next_sample).min(PLANAR_BLOCK_SAMPLES)).unwrap_or(0);
+        let mut components = vec![0; 2 * count * self.component_len];
+        let (i, q) = components.split_at_mut(count * self.component_len);
+        self.i.read_exact(i)?;
+        self.q.read_exact(q)?;
+        self.interleaved.clear();
+        for (i, q) in i.chunks_exact(self.component_len).zip(q.chunks_exact(self.component_len)) {
+            self.interleaved.extend_from_slice(i);
+            self.interleaved.extend_from_slice(q);
+        }
+        self.consumed = 0;
+        self.next_sample += count as u64;
+        Ok(())
+    }
+}
+
+impl Read for Planar {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.consumed == self.interleaved.len() {
+            self.fill()?;
+        }
+        let len = buf.len().min(self.interleaved.len() - self.consumed);
+        buf[..len].copy_from_slice(&self.interleaved[self.consumed..self.consumed + len]);
+        self.consumed += len;
+        Ok(len)
+    }
+}
+
+impl Seek for Planar {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let sample_len = 2 * self.component_len as u64;
+        let position = self.next_sample * sample_len - (self.interleaved.len() - self.consumed) as u64;
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.total_samples * sample_len).checked_add_signed(offset),
+            SeekFrom::Current(offset) => position.checked_add_signed(offset),
+        }
+        .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "Invalid seek to a negative position"))?;
+        // Positions past the end read nothing, like in a plain file
+        let sample = (target / sample_len).min(self.total_samples);
+        let component_len = self.component_len as u64;
+        self.i.seek(SeekFrom::Start(sample * component_len))?;
+        self.q.seek(SeekFrom::Start((self.total_samples + sample) * component_len))?;
+        self.next_sample = sample;
+        self.interleaved.clear();
+        self.consumed = 0;
+        if sample < self.total_samples && target % sample_len > 0 {
+            // Continue in the middle of the sample
+            self.fill()?;
+            self.consumed = usize::try_from(target % sample_len).unwrap_or(0);
+        }
+        Ok(target)
+    }
+}
+
+impl Read for Concat {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let read = self.files[self.current].read(buf)?;
+            if read > 0 || buf.is_empty() || self.current + 1 == self.files.len() {
+                self.position += read as u64;
+                return Ok(read);
+            }
+            // The current file ended, continue at the start of the next one
+            self.current += 1;
+            self.files[self.current].seek(SeekFrom::Start(0))?;
+        }
+    }
+}
+
+impl Seek for Concat {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let total_len = self.lens.iter().sum::<u64>();
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => total_len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        }
+        .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "Invalid seek to a negative position"))?;
+        // Find the file that contains the target, positions past the end belong to the last file
+        let mut start = 0;
+        self.current = self.files.len() - 1;
+        for (index, len) in self.lens.iter().enumerate() {
+            if target < start + len {
+                self.current = index;
+                break;
+            }
+            if index + 1 < self.lens.len() {
+                start += len;
+            }
+        }
+        self.files[self.current].seek(SeekFrom::Start(target - start))?;
+        self.position = target;
+        Ok(target)
+    }
+}
+
+/// Open the file at `path`, with the path in the error if that fails.
+pub(crate) fn open_file(path: &Path) -> Result<File, SdrReaderError> {
+    File::open(path).map_err(|source| SdrReaderError::Open { path: path.to_path_buf(), source })
+}
+
+impl InputFile {
+    fn from_kind(kind: Kind) -> Self {
+        InputFile { kind, window: None }
+    }
+
+    /// Open the file at `path`, decompressing it if `gzip` is set.
+    pub(crate) fn open(path: &Path, gzip: bool) -> Result<Self, SdrReaderError> {
+        let file = open_file(path)?;
+        Ok(if gzip { InputFile::from_kind(Kind::Gzip(GzDecoder::new(file))) } else { InputFile::from(file) })
+    }
+
+    /// Open the zstd-compressed file at `path`, to be decompressed while reading.
+    #[cfg(feature = "zstd")]
+    pub(crate) fn open_zstd(path: &Path) -> Result<Self, SdrReaderError> {
+        let file = open_file(path)?;
+        Ok(InputFile::from_kind(Kind::Zstd(zstd::Decoder::new(file)?)))
+    }
+
+    /// Open the file at `path` with a planar layout of `total_samples` samples,
+    /// to be read as interleaved samples with components of `component_len` bytes.
+    pub(crate) fn open_planar(path: &Path, component_len: usize, total_samples: u64) -> Result<Self, SdrReaderError> {
+        Ok(InputFile::from_kind(Kind::Planar(Planar {
+            i: open_file(path)?,
+            q: open_file(path)?,
+            component_len,
+            total_samples,
+            next_sample: 0,
+            interleaved: Vec::new(),
+            consumed: 0,
+        })))
+    }
+
+    /// Open the plain files at `paths`, to be read one after the other as a single stream.
+    /// There must be at least one path.
+    fn open_all(paths: Vec<PathBuf>) -> Result<Self, SdrReaderError> {
+        let files = paths.into_iter().map(|path| open_file(&path)).collect::<Result<Vec<_>, _>>()?;
+        let lens = files.iter().map(|file| file.metadata().map(|metadata| metadata.len())).collect::<Result<_, _>>()?;
+        Ok(InputFile::from_kind(Kind::Concat(Concat { files, lens, current: 0, position: 0 })))
+    }
+
+    /// Restrict reading to the bytes from `start` to `end`, which must not be less than `start`.
+    /// The range is clamped to the end of the file, less `footer_len` bytes at the end of the file that are never read.
+    /// Compressed files are read up to `start` instead of seeking, and their length is unknown, so the footer is ignored.
+    pub(crate) fn restrict(&mut self, start: u64, end: u64, footer_len: u64) -> Result<(), std::io::Error> {
+        let len = if self.is_compressed() {
+            std::io::copy(&mut self.take(start), &mut std::io::sink())?;
+            end - start
+        } else {
+            let file_len = self.seek(SeekFrom::End(0))?;
+            self.seek(SeekFrom::Start(start))?;
+            end.min(file_len.saturating_sub(footer_len)).saturating_sub(start)
+        };
+        self.window = Some(Window { start, len, position: 0 });
+        Ok(())
+    }
+
+    /// Whether the file is decompressed with gzip while reading.
+    #[must_use]
+    pub fn is_gzip(&self) -> bool {
+        matches!(self.kind, Kind::Gzip(_))
+    }
+
+    /// Whether the file is decompressed while reading, with gzip or zstd.
+    #[must_use]
+    pub fn is_compressed(&self) -> bool {
+        match self.kind {
+            Kind::Gzip(_) => true,
+            #[cfg(feature = "zstd")]
+            Kind::Zstd(_) => true,
+            Kind::Plain(_) | Kind::Concat(_) | Kind::Planar(_) => false,
+        }
+    }
+}
+
+impl From<File> for InputFile {
+    fn from(file: File) -> Self {
+        InputFile::from_kind(Kind::Plain(file))
+    }
+}
+
+impl Read for InputFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let buf = match &self.window {
+            Some(window) => {
+                let left = usize::try_from(window.len.saturating_sub(window.position)).unwrap_or(usize::MAX);
+                let len = buf.len().min(left);
+                &mut buf[..len]
+            }
+            None => buf,
+        };
+        let read = match &mut self.kind {
+            Kind::Plain(file) => file.read(buf),
+            Kind::Gzip(decoder) => decoder.read(buf),
+            #[cfg(feature = "zstd")]
+            Kind::Zstd(decoder) => decoder.read(buf),
+            Kind::Concat(concat) => concat.read(buf),
+            Kind::Planar(planar) => planar.read(buf),
+        }?;
+        if let Some(window) = &mut self.window {
+            window.position += read as u64;
+        }
+        Ok(read)
+    }
+}
+
+impl Seek for InputFile {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match &self.window {
+            Some(window) => {
+                let target = match pos {
+                    SeekFrom::Start(offset) => Some(offset),
+                    SeekFrom::End(offset) => window.len.checked_add_signed(offset),
+                    SeekFrom::Current(offset) => window.position.checked_add_signed(offset),
+                }
+                .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "Invalid seek to a negative position"))?;
+                SeekFrom::Start(window.start + target)
+            }
+            None => pos,
+        };
+        let position = match &mut self.kind {
+            Kind::Plain(file) => file.seek(pos),
+            Kind::Gzip(_) => Err(std::io::Error::new(ErrorKind::Unsupported, "Seeking is not supported in gzip-compressed files")),
+            #[cfg(feature = "zstd")]
+            Kind::Zstd(_) => Err(std::io::Error::new(ErrorKind::Unsupported, "Seeking is not supported in zstd-compressed files")),
+            Kind::Concat(concat) => concat.seek(pos),
+            Kind::Planar(planar) => planar.seek(pos),
+        }?;
+        match &mut self.window {
+            Some(window) => {
+                window.position = position - window.start;
+                Ok(window.position)
+            }
+            None => Ok(position),
+        }
+    }
+}
+
+#[bon]
+impl SdrFileReader {
+    /// Create a new `SdrFileReader` that reads the files at `paths` one after the other as a single stream,
+    /// e.g. a long capture split into `capture.000`, `capture.001`, ...
+    /// The files don't have to end on a chunk or even a sample boundary, chunks and samples continue in the next file.
+    /// Seeking and [`SdrFileReader::total_samples`] work on the combined stream.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::path::PathBuf;
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let paths = vec![PathBuf::from("capture.000"), PathBuf::from("capture.001")];
+    /// let mut reader = SdrFileReader::from_paths(paths, 1024, SampleType::I16)
+    ///     .build()
+    ///     .expect("Failed to open the files");
+    /// let samples = reader.read_next_chunk_complexf32().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `paths` is empty
+    /// - `SdrReaderError::Open` if any of the files could not be opened
+    #[builder(finish_fn = build)]
+    pub fn from_paths(
+        #[builder(start_fn)] paths: Vec<PathBuf>,
+        #[builder(start_fn)] samples_per_chunk: usize,
+        #[builder(start_fn)] sample_type: SampleType,
+        /// The byte order of multi-byte samples, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::endianness).
+        #[builder(default)] endianness: Endianness,
+        /// Center unsigned samples around zero, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::center_unsigned).
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0], see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::normalize).
+        #[builder(default)] normalize: bool,
+        /// What happens to the trailing bytes that don't make up a whole chunk, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::trailing_bytes).
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+        /// Metadata about the capture. If not set, it is parsed from the name of the first file
+        /// if that follows the gqrx naming scheme.
+        metadata: Option<CaptureMeta>,
+    ) -> Result<Self, SdrReaderError> {
+        let Some(first_path) = paths.first() else {
+            return Err(SdrReaderError::InvalidConfig("paths must not be empty".to_string()));
+        };
+        let metadata = metadata.or_else(|| parse_gqrx_filename(first_path));
+        SdrFileReader::from_reader(InputFile::open_all(paths)?, samples_per_chunk, sample_type)
+            .endianness(endianness)
+            .center_unsigned(center_unsigned)
+            .normalize(normalize)
+            .trailing_bytes(trailing_bytes)
+            .maybe_metadata(metadata)
+            .build()
+            .map(SdrFileReader::with_seek)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE_PATH: &str = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+
+    #[test]
+    fn test_from_paths_matches_joined_files() {
+        // Split the fixture after 1500 samples, in the middle of the second chunk
+        let bytes = std::fs::read(FILE_PATH).unwrap();
+        let split = 1500 * SampleType::F32.sample_len();
+        let paths = [&bytes[..split], &bytes[split..]]
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let path = std::env::temp_dir().join(format!("sdr_iq_file_reader_from_paths.{index:03}"));
+                std::fs::write(&path, part).unwrap();
+                path
+            })
+            .collect::<Vec<_>>();
+
+        let mut joined = Vec::new();
+        for path in &paths {
+            let mut reader = SdrFileReader::builder()
+                .file_path(path)
+                .samples_per_chunk(1024)
+                .sample_type(SampleType::F32)
+                .trailing_bytes(TrailingPolicy::PartialChunk)
+                .build()
+                .expect("Failed to create SdrFileReader");
+            joined.extend(reader.read_all_complexf32().unwrap());
+        }
+
+        let mut reader = SdrFileReader::from_paths(paths, 1024, SampleType::F32)
+            .build()
+            .expect("Failed to open the files");
+        assert_eq!(reader.total_samples().unwrap(), joined.len() as u64);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), joined[..1024]);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), joined[1024..2048]);
+        reader.seek_to_sample(1400).unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), joined[1400..2424]);
+        reader.reset().unwrap();
+        assert_eq!(reader.read_all_complexf32().unwrap(), joined);
+    }
+
+    #[test]
+    fn test_from_paths_rejects_empty() {
+        let result = SdrFileReader::from_paths(Vec::new(), 1024, SampleType::F32).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,64 @@
+//! Blind correction of the amplitude and phase imbalance between the I and Q components.
+
+use std::f64::consts::FRAC_PI_2;
+use num_complex::Complex;
+use num_traits::{Float, FromPrimitive};
+
+/// The weight of each new sample in the running estimates of the second moments.
+const MOMENT_ALPHA: f64 = 1e-4;
+
+/// The imbalance between the I and Q components estimated by the `correct_iq_imbalance` option.
+/// A balanced receiver has an `amplitude_ratio` of 1.0 and a `phase_error` of 0.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IqImbalance {
+    /// The amplitude of the Q component relative to the I component, `sqrt(E[Q²] / E[I²])`
+    pub amplitude_ratio: f64,
+    /// The deviation of the angle between the I and Q components from 90 degrees in radians,
+    /// `asin(E[IQ] / sqrt(E[I²] E[Q²]))`
+    pub phase_error: f64,
+}
+
+/// Running estimates of `E[I²]`, `E[Q²]` and `E[IQ]`, which carry over between chunks.
+/// Each estimate is a single-pole IIR low-pass like the DC offset estimate. They all start at zero,
+/// which biases them by the same factor at the start, so the ratios the imbalance is derived from are unbiased.
+#[derive(Clone, Default)]
+pub(crate) struct IqBalancer {
+    ii: f64,
+    qq: f64,
+    iq: f64,
+}
+
+impl IqBalancer {
+    /// The imbalance estimated from the samples processed so far, `None` before any nonzero sample.
+    pub(crate) fn imbalance(&self) -> Option<IqImbalance> {
+        if !(self.ii > 0.0 && self.qq > 0.0) {
+            return None;
+        }
+        let amplitude_ratio = (self.qq / self.ii).sqrt();
+        let phase_error = (self.iq / (self.ii * self.qq).sqrt()).clamp(-1.0, 1.0).asin();
+        Some(IqImbalance { amplitude_ratio, phase_error })
+    }
+
+    /// Update the estimates with the samples of a chunk, then correct them with the new estimate.
+    /// The samples should be free of a DC offset, which would distort the estimates.
+    /// The I component is kept, and the Q component is scaled and rotated to be orthogonal to it with the same power.
+    pub(crate) fn process<T: Float + FromPrimitive>(&mut self, samples: &mut [Complex<T>]) {
+        for sample in samples.iter() {
+            let (i, q) = (sample.re.to_f64().unwrap_or(f64::NAN), sample.im.to_f64().unwrap_or(f64::NAN));
+            self.ii += (i * i - self.ii) * MOMENT_ALPHA;
+            self.qq += (q * q - self.qq) * MOMENT_ALPHA;
+            self.iq += (i * q - self.iq) * MOMENT_ALPHA;
+        }
+        // Fully correlated components (a phase error of 90 degrees) can't be separated
+        let Some(imbalance) = self.imbalance().filter(|imbalance| imbalance.phase_error.abs() < FRAC_PI_2) else {
+            return;
+        };
+        // With I = a cos(θ) and Q = g a sin(θ + φ), Q / g = cos(φ) a sin(θ) + sin(φ) I
+        let (sin, cos) = imbalance.phase_error.sin_cos();
+        let q_from_q = T::from_f64(1.0 / (imbalance.amplitude_ratio * cos)).unwrap_or_else(T::nan);
+        let q_from_i = T::from_f64(-sin / cos).unwrap_or_else(T::nan);
+        for sample in samples {
+            sample.im = sample.im * q_from_q + sample.re * q_from_i;
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Consuming a reader as an iterator of chunks.
+
+use std::io::Read;
+use num_complex::Complex;
+use crate::{SdrFileReader, SdrReaderError};
+
+/// An iterator over the remaining chunks of an owned reader as Complex<f32>, created by the [`IntoIterator`]
+/// implementation of [`SdrFileReader`]. Use [`SdrFileReader::chunks_complexf64`] for the full precision of `F64` samples.
+pub struct IntoChunks<R> {
+    reader: SdrFileReader<R>,
+}
+
+impl<R> IntoChunks<R> {
+    /// Unwrap the iterator and return the reader, e.g. to read the rest of the file in another way.
+    #[must_use]
+    pub fn into_reader(self) -> SdrFileReader<R> {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for IntoChunks<R> {
+    type Item = Result<Vec<Complex<f32>>, SdrReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_next_chunk_complexf32().transpose()
+    }
+}
+
+impl<R: Read> IntoIterator for SdrFileReader<R> {
+    type Item = Result<Vec<Complex<f32>>, SdrReaderError>;
+    type IntoIter = IntoChunks<R>;
+
+    /// Iterate over the remaining chunks of the file as Complex<f32>, like [`SdrFileReader::chunks_complexf32`]
+    /// but taking ownership of the reader, e.g. in a `for` loop or to return the iterator from a function.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(1024)
+    ///     .sample_type(SampleType::F32)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// for chunk in reader {
+    ///     let samples = chunk.unwrap();
+    /// }
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoChunks { reader: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SampleType, TrailingPolicy};
+
+    #[test]
+    fn test_for_loop_over_reader() {
+        let bytes = (0..100u8).collect::<Vec<_>>();
+        let build = || SdrFileReader::from_bytes(bytes.clone(), 8, SampleType::U8).trailing_bytes(TrailingPolicy::PartialChunk).build().unwrap();
+        let expected = build().read_all_complexf32().unwrap();
+        let mut samples = Vec::new();
+        let mut chunks = 0;
+        for chunk in build() {
+            samples.extend(chunk.unwrap());
+            chunks += 1;
+        }
+        // 50 samples make 6 chunks of 8 and one of 2
+        assert_eq!(chunks, 7);
+        assert_eq!(samples, expected);
+
+        let mut iter = build().into_iter();
+        iter.next().unwrap().unwrap();
+        assert_eq!(iter.into_reader().read_next_chunk_complexf32().unwrap().unwrap(), expected[8..16]);
+    }
+}
@@ -2,12 +2,134 @@
 //!
 //! This library provides a simple way to read samples from an SDR IQ file through the [`SdrFileReader`] struct.
 //! See the [`SdrFileReader`] documentation for more information on how to use it.
+//! IQ recordings stored as WAV files can be read with [`SdrWavReader`],
+//! and `SigMF` recordings can be opened with [`SdrFileReader::from_sigmf`].
+//! Samples can be written back into raw IQ files with [`SdrFileWriter`].
+//!
+//! # Features
+//! - `std` (default): Enables everything that needs the standard library, i.e. all readers and writers.
+//!   Without it, the crate is `no_std` with `alloc`, and only decodes byte slices with [`decode_into`],
+//!   e.g. on embedded targets.
+//! - `tokio`: Enables `AsyncSdrFileReader`, which reads samples from a tokio `AsyncRead` source.
+//! - `memmap`: Enables `MmapSdrReader`, which memory-maps the whole file for random access.
+//! - `rayon`: Enables `SdrFileReader::read_next_chunk_complexf32_par`, which decodes chunks on multiple threads.
+//! - `half`: Enables reading and writing `SampleType::F16` half-precision float samples with the `half` crate.
+//! - `ndarray`: Enables `SdrFileReader::read_next_chunk_ndarray_f32` and `SdrFileReader::read_spectrogram_frames`,
+//!   which return chunks as `ndarray` arrays.
+//! - `rustfft`: Enables `chunk_peak_frequency`, which finds the strongest frequency in a chunk with an FFT,
+//!   and `SdrFileReader::spectrogram`, which computes the log-magnitude spectrogram of a capture.
+//! - `zstd`: Enables reading zstd-compressed files (`.zst`) with `SdrFileReader::builder`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-use std::fs::File;
-use std::io::{BufReader, ErrorKind, Read};
+extern crate alloc;
+
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::f64::consts::TAU;
+#[cfg(feature = "std")]
+use std::io::{BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom};
+#[cfg(feature = "std")]
+use std::ops::Range;
+#[cfg(feature = "std")]
 use std::path::Path;
+#[cfg(feature = "std")]
+use std::str::FromStr;
+#[cfg(feature = "std")]
+use std::time::Duration;
+#[cfg(feature = "std")]
 use bon::{bon};
+#[cfg(feature = "std")]
 use num_complex::{Complex};
+#[cfg(feature = "std")]
+use num_traits::{Float, FromPrimitive};
+
+#[cfg(feature = "std")]
+mod agc;
+#[cfg(feature = "ndarray")]
+mod array;
+#[cfg(feature = "tokio")]
+mod async_reader;
+#[cfg(feature = "std")]
+mod config;
+mod decode;
+#[cfg(feature = "std")]
+mod digest;
+#[cfg(feature = "std")]
+mod dsp;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "std")]
+mod filter;
+#[cfg(feature = "std")]
+mod input;
+#[cfg(feature = "std")]
+mod iq_balance;
+#[cfg(feature = "std")]
+mod iter;
+#[cfg(feature = "std")]
+mod metadata;
+#[cfg(feature = "memmap")]
+mod mmap_reader;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "std")]
+mod resample;
+#[cfg(feature = "std")]
+mod sigmf;
+#[cfg(feature = "std")]
+mod spawn;
+#[cfg(feature = "rustfft")]
+mod spectrum;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod transcode;
+#[cfg(feature = "std")]
+mod wav;
+#[cfg(feature = "std")]
+mod writer;
+
+#[cfg(feature = "std")]
+use agc::Agc;
+#[cfg(feature = "std")]
+use decode::DecodeOptions;
+#[cfg(feature = "std")]
+use dsp::MovingAverage;
+#[cfg(feature = "std")]
+use filter::LowPass;
+#[cfg(feature = "std")]
+use iq_balance::IqBalancer;
+#[cfg(feature = "std")]
+use resample::{Resampler, MAX_RATIO_TERM};
+
+#[cfg(feature = "tokio")]
+pub use async_reader::AsyncSdrFileReader;
+#[cfg(feature = "std")]
+pub use config::SdrReaderConfig;
+pub use decode::decode_into;
+#[cfg(feature = "std")]
+pub use error::SdrReaderError;
+#[cfg(feature = "std")]
+pub use input::InputFile;
+#[cfg(feature = "std")]
+pub use iq_balance::IqImbalance;
+#[cfg(feature = "std")]
+pub use iter::IntoChunks;
+#[cfg(feature = "std")]
+pub use metadata::{parse_gqrx_filename, CaptureMeta};
+#[cfg(feature = "memmap")]
+pub use mmap_reader::MmapSdrReader;
+#[cfg(feature = "std")]
+pub use sigmf::{SigmfAnnotation, SigmfMeta};
+#[cfg(feature = "rustfft")]
+pub use spectrum::{chunk_peak_frequency, WindowFunction};
+#[cfg(feature = "std")]
+pub use stats::{chunk_is_silent, chunk_stats, count_above_threshold, ChunkStats};
+#[cfg(feature = "std")]
+pub use wav::{SdrWavReader, WavHeader};
+#[cfg(feature = "std")]
+pub use writer::SdrFileWriter;
 
 /// Create a new `SdrFileReader` using the builder pattern.
 /// Then call `read_next_chunk_complexf32` or `read_next_chunk_complexf64` to read the samples.
@@ -23,14 +145,80 @@ use num_complex::{Complex};
 ///     .expect("Failed to create SdrFileReader");
 /// let samples = reader.read_next_chunk_complexf32().unwrap();
 /// ```
-pub struct SdrFileReader {
-    reader: BufReader<File>,
+///
+/// Samples can also be read from any other [`Read`] source (sockets, stdin, in-memory buffers, ...)
+/// by using [`SdrFileReader::from_reader`] instead.
+///
+/// The reader is `Send` if its source is, so it can be moved into a worker thread. This is checked by a test.
+#[cfg(feature = "std")]
+pub struct SdrFileReader<R = InputFile> {
+    reader: BufReader<R>,
     samples_per_chunk: usize,
-    sample_type: SampleType,
+    decode: DecodeOptions,
+    trailing_policy: TrailingPolicy,
+    pad_final_chunk: bool,
+    /// The number of zeros appended to the last chunk by `pad_final_chunk`
+    padded_samples: usize,
+    buffer: Vec<u8>,
+    metadata: Option<CaptureMeta>,
+    sigmf: Option<SigmfMeta>,
+    max_samples: Option<u64>,
+    samples_read: u64,
+    /// Stray bytes at the end of the source that did not make up a whole sample, reported after the final chunk
+    trailing_bytes: usize,
+    decimation_factor: usize,
+    /// The number of samples to drop at the start of the next chunk to stay in step with the decimation
+    decimation_skip: usize,
+    /// The phase advance per sample of the frequency shift in radians
+    shift_step: Option<f64>,
+    shift_phase: f64,
+    /// Decoded samples reused by the read methods that don't return `Complex` values
+    scratch: Vec<Complex<f32>>,
+    remove_dc: bool,
+    dc_offset: Complex<f64>,
+    iq_balancer: Option<IqBalancer>,
+    /// The product of `gain` and `complex_gain`, if either is set
+    gain: Option<Complex<f64>>,
+    agc: Option<Agc>,
+    sample_rate_hz: Option<u64>,
+    center_freq_hz: Option<u64>,
+    /// The number of samples each chunk shares with the previous one
+    overlap: usize,
+    /// The bytes at the end of the buffer that start the next chunk, if `overlap` is set
+    carry: Range<usize>,
+    /// The number of header bytes before the first sample
+    header_len: u64,
+    lowpass: Option<LowPass>,
+    resampler: Option<Resampler>,
+    /// The moving average of `read_next_chunk_smoothed_power_f32`, created by its first call
+    smoother: Option<MovingAverage>,
+    /// The number of interleaved IQ channels
+    channels: usize,
+    /// The CRC-32 of the bytes consumed so far, if `hashing` is set
+    hasher: Option<crc32fast::Hasher>,
+    /// The last sample returned by `read_next_chunk_phase_diff_f32`, to continue the phase differences in the next chunk
+    previous_sample: Option<Complex<f32>>,
+    /// Skips bytes by seeking instead of reading them, set for sources that are known to be seekable
+    #[allow(clippy::type_complexity)]
+    seek_forward: Option<fn(&mut BufReader<R>, u64) -> std::io::Result<u64>>,
+}
+
+/// The weight of each new sample in the running DC offset estimate.
+#[cfg(feature = "std")]
+const DC_ALPHA: f64 = 1e-3;
+
+/// One of the two components of a complex sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    /// The in-phase component, the real part
+    I,
+    /// The quadrature component, the imaginary part
+    Q,
 }
 
 /// The type of samples in the SDR file
 /// You will have to look up what your SDR/software uses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum SampleType {
     /// Samples stored as unsigned 8-bit integers
     U8,
@@ -40,40 +228,1060 @@ pub enum SampleType {
     U16,
     /// Samples stored as signed 16-bit integers
     I16,
+    /// Samples stored as signed 24-bit integers, packed into 3 bytes per component
+    I24,
+    /// Samples stored as 16-bit (half-precision) floating point numbers.
+    /// Reading and writing them requires the `half` feature.
+    F16,
     /// Samples stored as 32-bit floating point numbers
     F32,
     /// Samples stored as 64-bit floating point numbers
     F64,
 }
 
+/// The byte order in which multi-byte samples are stored in the SDR file.
+/// Most software (gqrx, `rtl_sdr`, ...) writes little-endian data.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Endianness {
+    /// Least significant byte first
+    #[default]
+    Little,
+    /// Most significant byte first
+    Big,
+    /// The byte order of the machine the code is running on
+    Native,
+}
+
+/// What happens to the bytes at the end of the file that don't make up a whole chunk,
+/// e.g. of a capture that was cut off or isn't a multiple of `samples_per_chunk` samples long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingPolicy {
+    /// Drop the trailing samples and any stray bytes after them silently
+    #[default]
+    Drop,
+    /// Return the trailing samples as a final chunk shorter than `samples_per_chunk`. Stray bytes after the last
+    /// whole sample are reported as [`SdrReaderError::UnexpectedPartialSample`] by the read following the final chunk.
+    PartialChunk,
+    /// Report the trailing bytes as [`SdrReaderError::UnexpectedPartialChunk`] instead of returning them,
+    /// e.g. for captures that must consist of whole chunks
+    Error,
+}
+
+#[cfg(feature = "std")]
+impl TrailingPolicy {
+    /// The number of bytes to decode from a chunk of `chunk_len` bytes when only `available` bytes are left.
+    /// 0 means that there is no chunk left, though stray bytes may still have to be reported.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::UnexpectedPartialChunk` if the chunk is incomplete with `TrailingPolicy::Error`
+    pub(crate) fn chunk_len(self, available: usize, chunk_len: usize, sample_len: usize) -> Result<usize, SdrReaderError> {
+        match self {
+            _ if available >= chunk_len => Ok(chunk_len),
+            TrailingPolicy::PartialChunk => Ok(available - available % sample_len),
+            TrailingPolicy::Error if available > 0 => Err(SdrReaderError::UnexpectedPartialChunk { bytes_left: available }),
+            TrailingPolicy::Drop | TrailingPolicy::Error => Ok(0),
+        }
+    }
+}
+
+/// How the I and Q components of the samples are arranged in the SDR file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// The components of each sample follow each other: `I0 Q0 I1 Q1 ...`
+    #[default]
+    Interleaved,
+    /// All I components are followed by all Q components: `I0 I1 ... Q0 Q1 ...`
+    Planar {
+        /// The number of samples in the file, where the Q block starts
+        total_samples: u64,
+    },
+}
+
+impl Endianness {
+    pub(crate) fn u16_from_bytes(&self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+            Endianness::Native => u16::from_ne_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn i16_from_bytes(&self, bytes: [u8; 2]) -> i16 {
+        match self {
+            Endianness::Little => i16::from_le_bytes(bytes),
+            Endianness::Big => i16::from_be_bytes(bytes),
+            Endianness::Native => i16::from_ne_bytes(bytes),
+        }
+    }
+
+    /// Decode a packed 24-bit signed integer, sign-extended into an `i32`.
+    pub(crate) fn i24_from_bytes(&self, bytes: [u8; 3]) -> i32 {
+        // Place the 3 bytes in the upper part of an i32, then use an arithmetic shift to sign-extend
+        let [b0, b1, b2] = bytes;
+        match self {
+            Endianness::Little => i32::from_le_bytes([0, b0, b1, b2]) >> 8,
+            Endianness::Big => i32::from_be_bytes([b0, b1, b2, 0]) >> 8,
+            #[cfg(target_endian = "little")]
+            Endianness::Native => i32::from_le_bytes([0, b0, b1, b2]) >> 8,
+            #[cfg(target_endian = "big")]
+            Endianness::Native => i32::from_be_bytes([b0, b1, b2, 0]) >> 8,
+        }
+    }
+
+    #[cfg(feature = "half")]
+    pub(crate) fn f16_from_bytes(&self, bytes: [u8; 2]) -> half::f16 {
+        match self {
+            Endianness::Little => half::f16::from_le_bytes(bytes),
+            Endianness::Big => half::f16::from_be_bytes(bytes),
+            Endianness::Native => half::f16::from_ne_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn f32_from_bytes(&self, bytes: [u8; 4]) -> f32 {
+        match self {
+            Endianness::Little => f32::from_le_bytes(bytes),
+            Endianness::Big => f32::from_be_bytes(bytes),
+            Endianness::Native => f32::from_ne_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn f64_from_bytes(&self, bytes: [u8; 8]) -> f64 {
+        match self {
+            Endianness::Little => f64::from_le_bytes(bytes),
+            Endianness::Big => f64::from_be_bytes(bytes),
+            Endianness::Native => f64::from_ne_bytes(bytes),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn u16_to_bytes(&self, value: u16) -> [u8; 2] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+            Endianness::Native => value.to_ne_bytes(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn i16_to_bytes(&self, value: i16) -> [u8; 2] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+            Endianness::Native => value.to_ne_bytes(),
+        }
+    }
+
+    /// Encode the lower 24 bits of `value` as a packed 24-bit signed integer.
+    #[cfg(feature = "std")]
+    pub(crate) fn i24_to_bytes(&self, value: i32) -> [u8; 3] {
+        let [b0, b1, b2, _] = value.to_le_bytes();
+        match self {
+            Endianness::Little => [b0, b1, b2],
+            Endianness::Big => [b2, b1, b0],
+            #[cfg(target_endian = "little")]
+            Endianness::Native => [b0, b1, b2],
+            #[cfg(target_endian = "big")]
+            Endianness::Native => [b2, b1, b0],
+        }
+    }
+
+    #[cfg(all(feature = "half", feature = "std"))]
+    pub(crate) fn f16_to_bytes(&self, value: half::f16) -> [u8; 2] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+            Endianness::Native => value.to_ne_bytes(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn f32_to_bytes(&self, value: f32) -> [u8; 4] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+            Endianness::Native => value.to_ne_bytes(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn f64_to_bytes(&self, value: f64) -> [u8; 8] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+            Endianness::Native => value.to_ne_bytes(),
+        }
+    }
+}
+
 impl SampleType {
     /// The number of bytes per sample.
     /// One sample has 2 values: I and Q.
-    /// Therefore the total number of bytes per sample twice the length of the datatype.
+    /// Therefore the total number of bytes per sample is twice the length of the datatype, e.g. 8 for `F32` and 16 for `F64`.
     #[must_use] pub fn sample_len(&self) -> usize {
         match self {
             SampleType::U8 | SampleType::I8 => 2,
-            SampleType::I16 | SampleType::U16=> 4,
+            SampleType::I16 | SampleType::U16 | SampleType::F16 => 4,
+            SampleType::I24 => 6,
             SampleType::F32 => 8,
             SampleType::F64 => 16,
         }
     }
+
+    /// The byte code of the sample type, e.g. for the header of a binary container, which [`SampleType::try_from`] maps back.
+    /// The codes are stable and don't depend on the enabled features:
+    ///
+    /// | Code | Sample type |
+    /// |------|-------------|
+    /// | 0    | `U8`        |
+    /// | 1    | `I8`        |
+    /// | 2    | `U16`       |
+    /// | 3    | `I16`       |
+    /// | 4    | `I24`       |
+    /// | 5    | `F16`       |
+    /// | 6    | `F32`       |
+    /// | 7    | `F64`       |
+    #[must_use]
+    pub fn as_u8(self) -> u8 {
+        match self {
+            SampleType::U8 => 0,
+            SampleType::I8 => 1,
+            SampleType::U16 => 2,
+            SampleType::I16 => 3,
+            SampleType::I24 => 4,
+            SampleType::F16 => 5,
+            SampleType::F32 => 6,
+            SampleType::F64 => 7,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<u8> for SampleType {
+    type Error = SdrReaderError;
+
+    /// Map a byte code to its sample type, see [`SampleType::as_u8`] for the mapping.
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(SampleType::U8),
+            1 => Ok(SampleType::I8),
+            2 => Ok(SampleType::U16),
+            3 => Ok(SampleType::I16),
+            4 => Ok(SampleType::I24),
+            5 => Ok(SampleType::F16),
+            6 => Ok(SampleType::F32),
+            7 => Ok(SampleType::F64),
+            _ => Err(SdrReaderError::InvalidConfig(format!("Unknown sample type code {code}"))),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for SampleType {
+    type Err = SdrReaderError;
+
+    /// Parse a sample type from its name, ignoring case.
+    /// Accepts the plain names (`u8`, `i16`, `f32`, ...), the complex names used by `SigMF` and `SoapySDR`
+    /// (`cu8`, `ci16`, `cs16`, `cf32`, ...), `SigMF` datatypes with a byte order suffix (`ci16_le`, `cf32_be`)
+    /// and the numpy names `complex64` and `complex128`.
+    /// The byte order suffix is only checked, not returned, as it is set separately with [`Endianness`].
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let lowercase = name.to_ascii_lowercase();
+        let format = lowercase
+            .strip_suffix("_le")
+            .or_else(|| lowercase.strip_suffix("_be"))
+            .unwrap_or(&lowercase);
+        match format {
+            "u8" | "cu8" | "uint8" => Ok(SampleType::U8),
+            "i8" | "ci8" | "s8" | "cs8" | "int8" => Ok(SampleType::I8),
+            "u16" | "cu16" | "uint16" => Ok(SampleType::U16),
+            "i16" | "ci16" | "s16" | "cs16" | "int16" => Ok(SampleType::I16),
+            "i24" | "ci24" | "s24" | "cs24" | "int24" => Ok(SampleType::I24),
+            "f16" | "cf16" | "fc16" | "float16" => Ok(SampleType::F16),
+            "f32" | "cf32" | "fc32" | "float32" | "complex64" => Ok(SampleType::F32),
+            "f64" | "cf64" | "fc64" | "float64" | "complex128" => Ok(SampleType::F64),
+            _ => Err(SdrReaderError::InvalidConfig(format!("Unknown sample type {name}"))),
+        }
+    }
 }
 
+impl Display for SampleType {
+    /// The plain name of the sample type, e.g. `i16`, which [`SampleType::from_str`] parses back.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            SampleType::U8 => "u8",
+            SampleType::I8 => "i8",
+            SampleType::U16 => "u16",
+            SampleType::I16 => "i16",
+            SampleType::I24 => "i24",
+            SampleType::F16 => "f16",
+            SampleType::F32 => "f32",
+            SampleType::F64 => "f64",
+        };
+        f.write_str(name)
+    }
+}
 
+
+#[cfg(feature = "std")]
 #[bon]
 impl SdrFileReader {
     #[allow(missing_docs)]
     #[builder]
-    pub fn new(file_path: impl AsRef<Path>, samples_per_chunk: usize, sample_type: SampleType) -> Result<Self, std::io::Error> {
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
+    pub fn new(
+        file_path: impl AsRef<Path>,
+        samples_per_chunk: usize,
+        sample_type: SampleType,
+        /// The byte order of multi-byte samples, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::endianness).
+        #[builder(default)] endianness: Endianness,
+        /// Center unsigned samples around zero, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::center_unsigned).
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0], see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::normalize).
+        #[builder(default)] normalize: bool,
+        /// Normalize by dividing by this value instead of the full-scale value of the sample type, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::normalize_with).
+        normalize_with: Option<f64>,
+        /// Shift integer components right by this many bits before the conversion, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::bit_shift).
+        #[builder(default)] bit_shift: u8,
+        /// Interpret signed integer samples as offset binary instead of two's complement, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::offset_binary).
+        #[builder(default)] offset_binary: bool,
+        /// What happens to the trailing bytes that don't make up a whole chunk, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::trailing_bytes).
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+        /// Fill the final chunk up to `samples_per_chunk` samples with zeros, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::pad_final_chunk).
+        #[builder(default)] pad_final_chunk: bool,
+        /// Metadata about the capture. If not set, it is parsed from the file name if that follows the gqrx naming scheme.
+        metadata: Option<CaptureMeta>,
+        /// Stop after this many samples have been read, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::max_samples).
+        max_samples: Option<u64>,
+        /// Keep only every nth sample after decoding, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::decimation_factor).
+        #[builder(default = 1)] decimation_factor: usize,
+        /// Shift the spectrum down by this frequency, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::frequency_shift_hz).
+        frequency_shift_hz: Option<f64>,
+        /// The sample rate of the recording in Hz, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::sample_rate_hz).
+        sample_rate_hz: Option<u64>,
+        /// The center frequency the recording was tuned to in Hz, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::center_freq_hz).
+        center_freq_hz: Option<u64>,
+        /// Swap the I and Q components of each sample, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::swap_iq).
+        #[builder(default)] swap_iq: bool,
+        /// Negate the imaginary part of each sample, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::conjugate).
+        #[builder(default)] conjugate: bool,
+        /// Subtract a running estimate of the DC offset from each sample, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::remove_dc).
+        #[builder(default)] remove_dc: bool,
+        /// Correct the amplitude and phase imbalance between the I and Q components, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::correct_iq_imbalance).
+        #[builder(default)] correct_iq_imbalance: bool,
+        /// Multiply each sample by this factor, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::gain).
+        gain: Option<f32>,
+        /// Multiply each sample by this complex factor, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::complex_gain).
+        complex_gain: Option<Complex<f32>>,
+        /// Scale each chunk toward a target RMS level with an automatic gain control, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::agc).
+        agc: Option<(f64, f64, f64)>,
+        /// Low-pass filter the samples with the given cutoff in Hz and number of taps, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::lowpass).
+        lowpass: Option<(f64, usize)>,
+        /// The capacity of the internal `BufReader` in bytes, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::buffer_capacity).
+        buffer_capacity: Option<usize>,
+        /// Let each chunk start with the last `overlap` samples of the previous chunk, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::overlap).
+        #[builder(default)] overlap: usize,
+        /// Convert the sample rate from the first to the second rate in Hz, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::resample).
+        resample: Option<(u64, u64)>,
+        /// The number of IQ channels interleaved sample by sample, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::channels).
+        #[builder(default = 1)] channels: usize,
+        /// Compute a CRC-32 of all raw bytes consumed from the source, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::hashing).
+        #[builder(default)] hashing: bool,
+        /// Skip this many bytes of a header before the first sample, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::header_skip_bytes).
+        #[builder(default)] header_skip_bytes: usize,
+        /// Treat the file as ending this many bytes before its actual end, e.g. for tools that append a JSON or
+        /// fixed-size footer after the samples, so the footer is not decoded as samples.
+        /// [`SdrFileReader::total_samples`] and [`SdrFileReader::remaining_samples`] don't count the footer,
+        /// and `trailing_bytes` decides about the chunk before it. Combines with `byte_range`, whose end is clamped
+        /// to the start of the footer. Can't be combined with compression or the planar layout. Defaults to 0.
+        #[builder(default)] footer_skip_bytes: usize,
+        /// Decompress the file with gzip while reading. If not set, files with a `.gz` extension are decompressed.
+        /// Gzip-compressed files can't be seeked, see [`InputFile`].
+        gzip: Option<bool>,
+        /// Decompress the file with zstd while reading. If not set, files with a `.zst` extension are decompressed.
+        /// Zstd-compressed files can't be seeked, see [`InputFile`]. Can't be combined with `gzip`,
+        /// and needs the `zstd` feature.
+        zstd: Option<bool>,
+        /// The arrangement of the I and Q components in the file. Defaults to [`Layout::Interleaved`].
+        /// The components of planar files are read from the two blocks of the file and interleaved,
+        /// so all other options work the same. Planar files can't be compressed or have a header.
+        #[builder(default)] layout: Layout,
+        /// Only read the bytes from the first to the second offset of the file, e.g. `(1_000_000, 5_000_000)`
+        /// to split the work on a large file between processes. The reader seeks to the start of the range
+        /// and treats its end like the end of the file, so [`SdrFileReader::total_samples`] and sample positions
+        /// are relative to the range, and `trailing_bytes` decides about the chunk at its end.
+        /// A header set with `header_skip_bytes` is skipped at the start of the range. The end is clamped to the file size.
+        byte_range: Option<(u64, u64)>,
+    ) -> Result<Self, SdrReaderError> {
+        let file_path = file_path.as_ref();
+        let metadata = metadata.or_else(|| parse_gqrx_filename(file_path));
+        let gzip = gzip.unwrap_or_else(|| file_path.extension().is_some_and(|extension| extension == "gz"));
+        let zstd = zstd.unwrap_or_else(|| file_path.extension().is_some_and(|extension| extension == "zst"));
+        if gzip && zstd {
+            return Err(SdrReaderError::InvalidConfig("gzip and zstd can't both be set".to_string()));
+        }
+        if zstd && !cfg!(feature = "zstd") {
+            return Err(SdrReaderError::InvalidConfig("Reading zstd-compressed files needs the zstd feature".to_string()));
+        }
+        let mut file = match layout {
+            #[cfg(feature = "zstd")]
+            Layout::Interleaved if zstd => InputFile::open_zstd(file_path)?,
+            Layout::Interleaved => InputFile::open(file_path, gzip)?,
+            Layout::Planar { .. } if gzip || zstd || header_skip_bytes > 0 => {
+                return Err(SdrReaderError::InvalidConfig(
+                    "The planar layout can't be combined with compression or header_skip_bytes".to_string(),
+                ))
+            }
+            Layout::Planar { total_samples } => InputFile::open_planar(file_path, sample_type.sample_len() / 2, total_samples)?,
+        };
+        if footer_skip_bytes > 0 && (file.is_compressed() || layout != Layout::Interleaved) {
+            return Err(SdrReaderError::InvalidConfig(
+                "footer_skip_bytes can't be combined with compression or the planar layout".to_string(),
+            ));
+        }
+        let footer_len = footer_skip_bytes as u64;
+        match byte_range {
+            Some((start, end)) if end < start => {
+                return Err(SdrReaderError::InvalidConfig("The end of byte_range must not be before its start".to_string()))
+            }
+            Some((start, end)) => file.restrict(start, end, footer_len)?,
+            None if footer_len > 0 => file.restrict(0, u64::MAX, footer_len)?,
+            None => {}
+        }
+        SdrFileReader::from_reader(file, samples_per_chunk, sample_type)
+            .endianness(endianness)
+            .center_unsigned(center_unsigned)
+            .normalize(normalize)
+            .maybe_normalize_with(normalize_with)
+            .bit_shift(bit_shift)
+            .offset_binary(offset_binary)
+            .trailing_bytes(trailing_bytes)
+            .pad_final_chunk(pad_final_chunk)
+            .maybe_metadata(metadata)
+            .maybe_max_samples(max_samples)
+            .decimation_factor(decimation_factor)
+            .maybe_frequency_shift_hz(frequency_shift_hz)
+            .maybe_sample_rate_hz(sample_rate_hz)
+            .maybe_center_freq_hz(center_freq_hz)
+            .swap_iq(swap_iq)
+            .conjugate(conjugate)
+            .remove_dc(remove_dc)
+            .correct_iq_imbalance(correct_iq_imbalance)
+            .maybe_gain(gain)
+            .maybe_complex_gain(complex_gain)
+            .maybe_agc(agc)
+            .maybe_lowpass(lowpass)
+            .maybe_buffer_capacity(buffer_capacity)
+            .overlap(overlap)
+            .maybe_resample(resample)
+            .channels(channels)
+            .hashing(hashing)
+            .header_skip_bytes(header_skip_bytes)
+            .build()
+            .map(SdrFileReader::with_seek)
+    }
+}
+
+#[cfg(feature = "std")]
+#[bon]
+impl SdrFileReader<Cursor<Vec<u8>>> {
+    /// Create a new `SdrFileReader` that reads samples from bytes already in memory,
+    /// e.g. to test code that processes samples without writing temporary files.
+    /// For the options not available here, pass `Cursor::new(bytes)` to [`SdrFileReader::from_reader`] instead.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let mut reader = SdrFileReader::from_bytes(vec![1, 0, 2, 0], 1, SampleType::I16).build().unwrap();
+    /// let samples = reader.read_next_chunk_complexf32().unwrap().unwrap();
+    /// assert_eq!(samples[0].im, 2.0);
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `samples_per_chunk` is 0
+    #[builder(finish_fn = build)]
+    pub fn from_bytes(
+        #[builder(start_fn)] bytes: Vec<u8>,
+        #[builder(start_fn)] samples_per_chunk: usize,
+        #[builder(start_fn)] sample_type: SampleType,
+        /// The byte order of multi-byte samples, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::endianness).
+        #[builder(default)] endianness: Endianness,
+        /// Center unsigned samples around zero, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::center_unsigned).
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0], see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::normalize).
+        #[builder(default)] normalize: bool,
+        /// What happens to the trailing bytes that don't make up a whole chunk, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::trailing_bytes).
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+        /// The sample rate of the recording in Hz, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::sample_rate_hz).
+        sample_rate_hz: Option<u64>,
+    ) -> Result<Self, SdrReaderError> {
+        SdrFileReader::from_reader(Cursor::new(bytes), samples_per_chunk, sample_type)
+            .endianness(endianness)
+            .center_unsigned(center_unsigned)
+            .normalize(normalize)
+            .trailing_bytes(trailing_bytes)
+            .maybe_sample_rate_hz(sample_rate_hz)
+            .build()
+            .map(SdrFileReader::with_seek)
+    }
+}
+
+#[cfg(feature = "std")]
+#[bon]
+impl<R: Read> SdrFileReader<R> {
+    /// Create a new `SdrFileReader` that reads samples from any [`Read`] source instead of a file path.
+    /// The remaining options can be set on the returned builder, just like with [`SdrFileReader::builder`].
+    /// The options are documented in full on this builder, the other constructors link to it.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let bytes = vec![0u8; 4096];
+    /// let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 1024, SampleType::I16).build().unwrap();
+    /// let samples = reader.read_next_chunk_complexf32().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `samples_per_chunk` is 0
+    /// - `SdrReaderError::InvalidConfig` if `frequency_shift_hz` is set without a nonzero sample rate
+    /// - `SdrReaderError::InvalidConfig` if `overlap` is not less than `samples_per_chunk`
+    /// - `SdrReaderError::InvalidConfig` if `decimation_factor` is greater than `samples_per_chunk`
+    /// - `SdrReaderError::InvalidConfig` if `normalize_with` is not positive and finite
+    /// - `SdrReaderError::InvalidConfig` if `bit_shift` is not less than the number of bits of a component
+    /// - `SdrReaderError::InvalidConfig` if the sample type is `F16` and the `half` feature is disabled
+    /// - `SdrReaderError::InvalidConfig` if a `resample` rate is 0, the reduced ratio has a term above 10000,
+    ///   or `resample` is combined with `overlap`
+    /// - `SdrReaderError::InvalidConfig` if `pad_final_chunk` is combined with decimation or resampling
+    /// - `SdrReaderError::InvalidConfig` if the `agc` target RMS is not positive and finite,
+    ///   or its attack or decay is not between 0 and 1
+    /// - `SdrReaderError::InvalidConfig` if the `lowpass` cutoff is not between 0 and half the sample rate,
+    ///   the sample rate is not known, it has no taps, or it is combined with `overlap`
+    /// - `SdrReaderError::InvalidConfig` if `channels` is 0, doesn't divide `samples_per_chunk` and `overlap`,
+    ///   or is combined with the DC removal, IQ imbalance correction, frequency shift, low-pass filter, decimation or resampling
+    /// - `SdrReaderError::Io` if there was an error reading the header
+    #[builder(finish_fn = build)]
+    #[allow(clippy::too_many_lines)]
+    pub fn from_reader(
+        #[builder(start_fn)] reader: R,
+        #[builder(start_fn)] samples_per_chunk: usize,
+        #[builder(start_fn)] sample_type: SampleType,
+        /// The byte order of multi-byte samples. Defaults to [`Endianness::Little`].
+        #[builder(default)] endianness: Endianness,
+        /// Subtract the midpoint (127.5 for `U8`, 32767.5 for `U16`) from unsigned samples so they are centered around zero.
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0] by dividing by the full-scale value of the sample type.
+        /// Float sample types are passed through unchanged.
+        #[builder(default)] normalize: bool,
+        /// Normalize by dividing by this value instead of the full-scale value of the sample type, implies `normalize`.
+        /// For an ADC with `b` bits, right-justified samples have a full scale of `2^(b-1)`, e.g. 2048 for 12 bits
+        /// in 16-bit words, and left-justified samples `2^(b-1) * 2^(16-b)`, e.g. 2048 * 16 = 32768.
+        /// Unlike `normalize`, this also applies to float sample types. Must be positive and finite.
+        normalize_with: Option<f64>,
+        /// Shift integer components right by this many bits before the conversion, e.g. 4 to drop the noise bits
+        /// of 12-bit samples left-justified in 16-bit words. The shift is arithmetic for signed types.
+        /// Applied before `center_unsigned` and the normalization, which still use the full scale of the sample type,
+        /// so use `normalize_with` to match the shifted range. Float sample types are passed through unchanged.
+        /// Must be less than the number of bits of a component. Defaults to 0.
+        #[builder(default)] bit_shift: u8,
+        /// Interpret signed integer samples as offset binary instead of two's complement, as output by some ADCs:
+        /// the most significant bit of each component is flipped before the conversion, so `0x0000` is the most negative
+        /// value and `0x8000` is zero for `I16`. Applied before `bit_shift`.
+        /// Unsigned samples are already offset binary, see `center_unsigned`, and float samples are passed through unchanged.
+        #[builder(default)] offset_binary: bool,
+        /// What happens to the bytes at the end of the file that don't make up a whole chunk. Defaults to [`TrailingPolicy::Drop`].
+        /// With the default, a file with fewer than `samples_per_chunk` samples yields no chunks at all,
+        /// with [`TrailingPolicy::PartialChunk`], all of its samples are returned as a single short chunk.
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+        /// Fill the final chunk up to `samples_per_chunk` samples with zeros, e.g. for FFTs of a fixed size.
+        /// The number of zeros is available through [`SdrFileReader::padded_samples`].
+        /// Takes precedence over `trailing_bytes`: the final short chunk is always returned padded instead of being dropped
+        /// or reported, like with [`TrailingPolicy::PartialChunk`]. Bytes that don't make up a whole sample are still reported.
+        /// Can't be combined with decimation or resampling, which change the length of the chunks.
+        #[builder(default)] pad_final_chunk: bool,
+        /// Metadata about the capture, available through [`SdrFileReader::metadata`].
+        metadata: Option<CaptureMeta>,
+        /// Stop after this many samples have been read, even if the source contains more.
+        /// The last chunk is shortened so the limit is not exceeded.
+        max_samples: Option<u64>,
+        /// Keep only every nth sample after decoding, so each chunk holds about `samples_per_chunk / n` samples.
+        /// This is a naive decimation without a low-pass filter, so signals outside the new bandwidth alias into it.
+        /// Defaults to 1 (no decimation), 0 is treated as 1. `max_samples` counts the samples before decimation.
+        /// Must not be greater than `samples_per_chunk`, so every chunk keeps at least one sample.
+        #[builder(default = 1)] decimation_factor: usize,
+        /// Shift the spectrum down by this frequency by multiplying each sample with `e^{-j2πfn/fs}`,
+        /// so a signal at `+f` ends up at DC. Requires the sample rate to be known.
+        /// The phase carries over between chunks, so there is no discontinuity at chunk boundaries.
+        frequency_shift_hz: Option<f64>,
+        /// The sample rate of the recording in Hz. If not set, it is taken from the metadata.
+        sample_rate_hz: Option<u64>,
+        /// The center frequency the recording was tuned to in Hz. If not set, it is taken from the metadata.
+        center_freq_hz: Option<u64>,
+        /// Swap the I and Q components of each sample, which mirrors the spectrum.
+        /// Useful for hardware or recordings that store Q before I.
+        #[builder(default)] swap_iq: bool,
+        /// Negate the imaginary part of each sample, which mirrors the spectrum.
+        /// Applied after `swap_iq` and before the frequency shift.
+        #[builder(default)] conjugate: bool,
+        /// Subtract a running estimate of the DC offset (the spike at the tuned frequency) from each sample.
+        /// The estimate carries over between chunks and converges over the first few thousand samples.
+        #[builder(default)] remove_dc: bool,
+        /// Correct the amplitude and phase imbalance between the I and Q components of the hardware, which shows up
+        /// as a mirror image of each signal on the other side of the spectrum. The imbalance is estimated blindly from
+        /// running averages of `I²`, `Q²` and `IQ` that carry over between chunks and converge over the first few
+        /// ten thousand samples, and is available through [`SdrFileReader::iq_imbalance`]. Each chunk is corrected
+        /// with the estimate after it. Applied after the DC removal, which should be enabled if the samples have a DC offset.
+        #[builder(default)] correct_iq_imbalance: bool,
+        /// Multiply each sample by this factor, e.g. to match the levels of different recordings.
+        /// Applied after the normalization and the DC removal.
+        gain: Option<f32>,
+        /// Multiply each sample by this complex factor, which scales and rotates it. Combines with `gain`.
+        complex_gain: Option<Complex<f32>>,
+        /// Scale each chunk toward a target RMS level with a block-based automatic gain control, e.g. `(0.5, 0.5, 0.05)`
+        /// for demodulators that expect a roughly constant amplitude. The tuple holds the target RMS, the attack and the decay.
+        /// Per chunk, the gain moves toward `target_rms / rms` by the attack fraction when the level rises and by the decay
+        /// fraction when it falls, so both must be between 0 (exclusive) and 1. The gain carries over between chunks,
+        /// starts at 1.0, and is available through [`SdrFileReader::agc_gain`]. Applied after `gain`.
+        agc: Option<(f64, f64, f64)>,
+        /// Low-pass filter the samples with a windowed-sinc FIR filter (with a Hamming window) of the given cutoff
+        /// in Hz and number of taps, e.g. `(100_000.0, 101)`, to isolate a channel or to prevent aliasing before the decimation.
+        /// More taps give a steeper transition, and the output is delayed by `(num_taps - 1) / 2` samples.
+        /// The filter state carries over between chunks, seeking and skipping reset it. Applied after the frequency shift.
+        /// Requires the sample rate to be known, and the cutoff must be below half of it. Can't be combined with `overlap`.
+        lowpass: Option<(f64, usize)>,
+        /// The capacity of the internal `BufReader` in bytes. Defaults to 8 KiB.
+        /// For large sequential reads, a few times the byte size of a chunk
+        /// (`samples_per_chunk * sample_type.sample_len()`) reduces the number of reads from the source.
+        buffer_capacity: Option<usize>,
+        /// Let each chunk start with the last `overlap` samples of the previous chunk, e.g. `samples_per_chunk / 2`
+        /// for FFT windows with 50% overlap. The overlapping samples are kept in memory, so any source works.
+        /// The frequency shift and decimation of the repeated samples match the previous chunk, the DC removal does not.
+        /// Must be less than `samples_per_chunk`. Defaults to 0.
+        #[builder(default)] overlap: usize,
+        /// Convert the sample rate from the first to the second rate in Hz, e.g. `(2_400_000, 2_048_000)`, with a polyphase
+        /// resampler for the reduced ratio (75 / 64 in the example). The filter state carries over between chunks,
+        /// so there are no glitches at chunk boundaries, but the number of samples per chunk varies (and may even be 0
+        /// for small chunks). The output is delayed by the filter by about 10 samples of the lower rate.
+        /// Applied after the decimation. Seeking and skipping reset the filter state. Can't be combined with `overlap`.
+        /// The terms of the reduced ratio must be at most 10000, which limits the filter to about 200 000 taps.
+        resample: Option<(u64, u64)>,
+        /// The number of IQ channels interleaved sample by sample, e.g. of multi-antenna receivers: `I0 Q0 I1 Q1 ...`.
+        /// Chunks hold the samples of all channels, and [`SdrFileReader::read_next_chunk_channel_f32`] returns a single channel.
+        /// `samples_per_chunk` and `overlap` count the samples of all channels, so they must be multiples of `channels`.
+        /// Can't be combined with the DC removal, IQ imbalance correction, frequency shift, low-pass filter, decimation
+        /// or resampling. Defaults to 1.
+        #[builder(default = 1)] channels: usize,
+        /// Compute a CRC-32 of all raw bytes consumed from the source (including the skipped header and samples),
+        /// available through [`SdrFileReader::digest`], e.g. to detect when an input file changed between runs.
+        #[builder(default)] hashing: bool,
+        /// Skip this many bytes of a header before the first sample, e.g. of a custom capture tool.
+        /// Sample positions and counts like [`SdrFileReader::total_samples`] start after the header. Defaults to 0.
+        #[builder(default)] header_skip_bytes: usize,
+    ) -> Result<Self, SdrReaderError> {
+        if samples_per_chunk == 0 {
+            return Err(SdrReaderError::InvalidConfig("samples_per_chunk must not be 0".to_string()));
+        }
+        if overlap >= samples_per_chunk {
+            return Err(SdrReaderError::InvalidConfig("overlap must be less than samples_per_chunk".to_string()));
+        }
+        if decimation_factor > samples_per_chunk {
+            return Err(SdrReaderError::InvalidConfig("decimation_factor must not be greater than samples_per_chunk".to_string()));
+        }
+        let resampler = match resample {
+            None => None,
+            Some((0, _) | (_, 0)) => return Err(SdrReaderError::InvalidConfig("resample rates must not be 0".to_string())),
+            Some(_) if overlap > 0 => {
+                return Err(SdrReaderError::InvalidConfig("resample can't be combined with overlap".to_string()))
+            }
+            Some((from_hz, to_hz)) => Some(Resampler::new(from_hz, to_hz).ok_or_else(|| {
+                SdrReaderError::InvalidConfig(format!(
+                    "The resample ratio {to_hz} / {from_hz} must reduce to terms of at most {MAX_RATIO_TERM}"
+                ))
+            })?),
+        };
+        if pad_final_chunk && (decimation_factor > 1 || resampler.is_some()) {
+            return Err(SdrReaderError::InvalidConfig("pad_final_chunk can't be combined with decimation or resampling".to_string()));
+        }
+        if channels == 0 || !samples_per_chunk.is_multiple_of(channels) || !overlap.is_multiple_of(channels) {
+            return Err(SdrReaderError::InvalidConfig(
+                "channels must be nonzero and divide samples_per_chunk and overlap".to_string(),
+            ));
+        }
+        let processing = remove_dc || correct_iq_imbalance || frequency_shift_hz.is_some() || lowpass.is_some() || decimation_factor > 1 || resampler.is_some();
+        if channels > 1 && processing {
+            return Err(SdrReaderError::InvalidConfig(
+                "channels can't be combined with DC removal, IQ imbalance correction, frequency shift, low-pass filter, decimation or resampling"
+                    .to_string(),
+            ));
+        }
+        let sample_rate_hz = sample_rate_hz.or(metadata.as_ref().map(|metadata| metadata.sample_rate_hz));
+        let center_freq_hz = center_freq_hz.or(metadata.as_ref().map(|metadata| metadata.center_freq_hz));
+        let shift_step = match (frequency_shift_hz, sample_rate_hz) {
+            (None, _) => None,
+            #[allow(clippy::cast_precision_loss)]
+            (Some(shift_hz), Some(rate_hz)) if rate_hz > 0 => Some(-std::f64::consts::TAU * shift_hz / rate_hz as f64),
+            (Some(_), _) => {
+                return Err(SdrReaderError::InvalidConfig("frequency_shift_hz requires a nonzero sample_rate_hz".to_string()))
+            }
+        };
+        let lowpass = LowPass::from_options(lowpass, sample_rate_hz, overlap)?;
+        let agc = Agc::from_options(agc)?;
+        let mut reader = match buffer_capacity {
+            Some(capacity) => BufReader::with_capacity(capacity, reader),
+            None => BufReader::new(reader),
+        };
+        // Read past the header instead of seeking, so it works for any source
+        let header_len = header_skip_bytes as u64;
+        let mut hasher = hashing.then(crc32fast::Hasher::new);
+        digest::discard(&mut reader, header_len, hasher.as_mut())?;
         Ok(SdrFileReader {
             reader,
             samples_per_chunk,
-            sample_type,
+            decode: DecodeOptions {
+                sample_type,
+                endianness,
+                center_unsigned,
+                normalize: normalize || normalize_with.is_some(),
+                swap_iq,
+                conjugate,
+                full_scale: normalize_with,
+                bit_shift,
+                offset_binary,
+                byte_table: None,
+            }
+            .validate()?
+            .with_byte_table(),
+            trailing_policy: trailing_bytes,
+            pad_final_chunk,
+            padded_samples: 0,
+            buffer: Vec::new(),
+            metadata,
+            sigmf: None,
+            max_samples,
+            samples_read: 0,
+            trailing_bytes: 0,
+            decimation_factor: decimation_factor.max(1),
+            decimation_skip: 0,
+            shift_step,
+            shift_phase: 0.0,
+            scratch: Vec::new(),
+            remove_dc,
+            dc_offset: Complex::new(0.0, 0.0),
+            iq_balancer: correct_iq_imbalance.then(IqBalancer::default),
+            gain: dsp::combine_gains(gain, complex_gain),
+            agc,
+            sample_rate_hz,
+            center_freq_hz,
+            overlap,
+            carry: 0..0,
+            header_len,
+            lowpass,
+            resampler,
+            smoother: None,
+            channels,
+            hasher,
+            previous_sample: None,
+            seek_forward: None,
         })
     }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> SdrFileReader<R> {
+    /// The CRC-32 (IEEE) of all raw bytes consumed from the source so far, if `hashing` is set.
+    /// Reading a whole file gives the same digest as hashing the file with a standard CRC-32 tool.
+    /// Bytes that are read again after seeking back are hashed again, peeked bytes are not hashed,
+    /// and neither are the bytes of an incomplete final chunk that is dropped.
+    #[must_use]
+    pub fn digest(&self) -> Option<u32> {
+        self.hasher.clone().map(crc32fast::Hasher::finalize)
+    }
+
+    /// The sample type the reader decodes.
+    #[must_use]
+    pub fn sample_type(&self) -> SampleType {
+        self.decode.sample_type
+    }
+
+    /// The number of zeros appended to the last chunk by the `pad_final_chunk` option, so the samples before them are
+    /// the real ones. 0 for all chunks but the final one, or if the option is not set.
+    #[must_use]
+    pub fn padded_samples(&self) -> usize {
+        self.padded_samples
+    }
+
+    /// The number of samples in each chunk, before decimation.
+    #[must_use]
+    pub fn samples_per_chunk(&self) -> usize {
+        self.samples_per_chunk
+    }
+
+    /// The number of bytes in each chunk, `samples_per_chunk * sample_type.sample_len()`.
+    #[must_use]
+    pub fn bytes_per_chunk(&self) -> usize {
+        self.samples_per_chunk * self.decode.sample_type.sample_len()
+    }
+
+    /// The current estimate of the DC offset that is subtracted when `remove_dc` is set.
+    /// Stays at zero if DC removal is disabled.
+    #[must_use]
+    pub fn dc_offset(&self) -> Complex<f64> {
+        self.dc_offset
+    }
+
+    /// The imbalance between the I and Q components estimated when `correct_iq_imbalance` is set.
+    /// Returns `None` if the correction is disabled or no nonzero samples have been read yet.
+    #[must_use]
+    pub fn iq_imbalance(&self) -> Option<IqImbalance> {
+        self.iq_balancer.as_ref().and_then(IqBalancer::imbalance)
+    }
+
+    /// The gain the automatic gain control applied to the last chunk.
+    /// Stays at 1.0 if the AGC is disabled.
+    #[must_use]
+    pub fn agc_gain(&self) -> f64 {
+        self.agc.as_ref().map_or(1.0, Agc::gain)
+    }
+
+    /// Metadata about the capture, if it was set on the builder or parsed from a gqrx file name.
+    #[must_use]
+    pub fn metadata(&self) -> Option<&CaptureMeta> {
+        self.metadata.as_ref()
+    }
+
+    /// The sample rate of the recording in Hz, if it was set on the builder or is known from the metadata.
+    #[must_use]
+    pub fn sample_rate_hz(&self) -> Option<u64> {
+        self.sample_rate_hz
+    }
+
+    /// The center frequency of the recording in Hz, if it was set on the builder or is known from the metadata.
+    #[must_use]
+    pub fn center_freq_hz(&self) -> Option<u64> {
+        self.center_freq_hz
+    }
+
+    /// The time from the start of the capture to the sample at `sample_index`, rounded down to whole nanoseconds.
+    /// Returns `None` if the sample rate is not known.
+    #[must_use]
+    pub fn sample_to_duration(&self, sample_index: u64) -> Option<Duration> {
+        let rate = self.sample_rate_hz.filter(|&rate| rate > 0)?;
+        let nanos = u128::from(sample_index % rate) * 1_000_000_000 / u128::from(rate);
+        Some(Duration::from_secs(sample_index / rate) + Duration::from_nanos(u64::try_from(nanos).ok()?))
+    }
+
+    /// The index of the sample at `duration` after the start of the capture, rounded down.
+    /// Returns `None` if the sample rate is not known or the index doesn't fit into a `u64`.
+    #[must_use]
+    pub fn duration_to_sample(&self, duration: Duration) -> Option<u64> {
+        let rate = self.sample_rate_hz.filter(|&rate| rate > 0)?;
+        u64::try_from(duration.as_nanos() * u128::from(rate) / 1_000_000_000).ok()
+    }
+
+    /// The parsed `SigMF` metadata, if the reader was created with [`SdrFileReader::from_sigmf`].
+    #[must_use]
+    pub fn sigmf_meta(&self) -> Option<&SigmfMeta> {
+        self.sigmf.as_ref()
+    }
+
+    /// The annotations of a `SigMF` recording. Empty for other recordings.
+    #[must_use]
+    pub fn annotations(&self) -> &[SigmfAnnotation] {
+        self.sigmf.as_ref().map_or(&[], |sigmf| &sigmf.annotations)
+    }
+
+    /// Unwrap the reader and return the underlying source, e.g. to read metadata that follows the samples.
+    /// The source is returned with its `BufReader`, so bytes that were buffered ahead but not decoded yet are not lost.
+    /// Reading continues right after the last sample returned, except that the bytes of an incomplete final chunk
+    /// that was dropped have been consumed.
+    #[must_use]
+    pub fn into_inner(self) -> BufReader<R> {
+        self.reader
+    }
+
+    /// Read the raw bytes of the next chunk into the internal buffer.
+    /// Only whole samples are kept, so the returned length is always a multiple of `sample_type.sample_len()`.
+    /// With `overlap` set, the chunk starts with the overlapping bytes of the previous chunk.
+    /// Returns `Ok(None)` if no (complete) chunk is left.
+    fn read_chunk_bytes(&mut self) -> Result<Option<usize>, SdrReaderError> {
+        let sample_len = self.decode.sample_type.sample_len();
+        let carried = self.carry.len();
+        if carried > 0 {
+            self.buffer.copy_within(self.carry.clone(), 0);
+            self.rewind(carried / sample_len);
+            self.carry = 0..0;
+        }
+        let mut new_samples = self.samples_per_chunk - carried / sample_len;
+        if let Some(max_samples) = self.max_samples {
+            let samples_left = max_samples.saturating_sub(self.samples_read);
+            if samples_left == 0 {
+                return Ok(None);
+            }
+            new_samples = new_samples.min(usize::try_from(samples_left).unwrap_or(usize::MAX));
+        }
+        let chunk_len = carried + new_samples * sample_len;
+        self.buffer.resize(chunk_len, 0);
+        let policy = if self.pad_final_chunk { TrailingPolicy::PartialChunk } else { self.trailing_policy };
+        if policy == TrailingPolicy::Drop {
+            // `read_exact` retries reads that fail with `ErrorKind::Interrupted`, like the loop below
+            return match self.reader.read_exact(&mut self.buffer[carried..]) {
+                Ok(()) => {
+                    if let Some(hasher) = &mut self.hasher {
+                        hasher.update(&self.buffer[carried..]);
+                    }
+                    self.samples_read += new_samples as u64;
+                    Ok(Some(self.keep_overlap(chunk_len)))
+                }
+                Err(why) => match why.kind() {
+                    ErrorKind::UnexpectedEof => Ok(None),
+                    _ => Err(why.into()),
+                },
+            };
+        }
+
+        let mut filled = carried;
+        while filled < chunk_len {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(why) if why.kind() == ErrorKind::Interrupted => {}
+                Err(why) => return Err(why.into()),
+            }
+        }
+        if let Some(hasher) = &mut self.hasher {
+            hasher.update(&self.buffer[carried..filled]);
+        }
+        let new_len = filled - carried;
+        let whole_samples_len = policy.chunk_len(new_len, chunk_len - carried, sample_len)?;
+        if whole_samples_len == 0 {
+            // Report stray bytes once the last whole samples have been returned
+            let bytes_left = std::mem::take(&mut self.trailing_bytes) + new_len;
+            return match bytes_left {
+                0 => Ok(None),
+                _ => Err(SdrReaderError::UnexpectedPartialSample { bytes_left }),
+            };
+        }
+        self.trailing_bytes = new_len - whole_samples_len;
+        self.samples_read += (whole_samples_len / sample_len) as u64;
+        Ok(Some(self.keep_overlap(carried + whole_samples_len)))
+    }
+
+    /// Forget the state of the low-pass filter, the resampler and the power smoothing after jumping to another position.
+    fn reset_filters(&mut self) {
+        if let Some(smoother) = &mut self.smoother {
+            smoother.reset();
+        }
+        if let Some(lowpass) = &mut self.lowpass {
+            lowpass.reset();
+        }
+        if let Some(resampler) = &mut self.resampler {
+            resampler.reset();
+        }
+    }
+
+    /// Remember the last `overlap` samples of a chunk of `len` bytes to start the next chunk with.
+    /// Returns `len` for convenience.
+    fn keep_overlap(&mut self, len: usize) -> usize {
+        if self.overlap > 0 {
+            let overlap_len = self.overlap * self.decode.sample_type.sample_len();
+            self.carry = len.saturating_sub(overlap_len)..len;
+        }
+        len
+    }
+
+    /// Step the frequency shift and decimation back by `count` samples, for the overlapping samples that are decoded again.
+    fn rewind(&mut self, count: usize) {
+        self.decimation_skip = (self.decimation_skip + count) % self.decimation_factor;
+        if let Some(step) = self.shift_step {
+            #[allow(clippy::cast_precision_loss)]
+            let phase = (self.shift_phase - step * count as f64).rem_euclid(TAU);
+            self.shift_phase = phase;
+        }
+    }
+
+    /// Step the frequency shift and decimation forward by `count` samples that are skipped without decoding.
+    fn fast_forward(&mut self, count: u64) {
+        let n = self.decimation_factor as u64;
+        let skip = self.decimation_skip as u64;
+        self.decimation_skip = usize::try_from((skip + n - count % n) % n).unwrap_or(0);
+        if let Some(step) = self.shift_step {
+            #[allow(clippy::cast_precision_loss)]
+            let phase = (self.shift_phase + step * count as f64).rem_euclid(TAU);
+            self.shift_phase = phase;
+        }
+    }
+
+    /// Skip the next `n` samples without decoding them.
+    /// Readers opened from a path or from bytes seek over the skipped bytes. Readers created with
+    /// [`SdrFileReader::from_reader`], readers with `hashing` set and compressed files read and discard them instead.
+    ///
+    /// The frequency shift and decimation continue as if the skipped samples had been read, and skipped samples
+    /// count towards `max_samples`. Skipping past the end of the source is allowed, the next read then returns `Ok(None)`.
+    /// Use [`SdrFileReader::advance_samples`] to find out how many samples were actually skipped.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the source
+    pub fn skip_samples(&mut self, n: u64) -> Result<(), SdrReaderError> {
+        self.advance_samples(n)?;
+        Ok(())
+    }
+
+    /// Like [`SdrFileReader::skip_samples`], but returns the number of samples that were skipped,
+    /// which is less than `n` if the source ended. No bytes are decoded, and seekable sources don't read them either.
+    ///
+    /// # Errors
+    /// - `std::io::Error` if there was an error reading the source
+    pub fn advance_samples(&mut self, n: u64) -> Result<u64, std::io::Error> {
+        let sample_len = self.decode.sample_type.sample_len();
+        // The overlapping samples kept for the next chunk come first
+        let from_carry = n.min((self.carry.len() / sample_len) as u64);
+        self.carry.start += usize::try_from(from_carry).unwrap_or(0) * sample_len;
+        let from_source = n - from_carry;
+        if from_source > 0 {
+            let len = from_source.saturating_mul(sample_len as u64);
+            let skipped_len = match self.seek_forward {
+                // The digest needs every byte, so they are still read when hashing
+                Some(seek_forward) if self.hasher.is_none() => match seek_forward(&mut self.reader, len) {
+                    Err(why) if why.kind() == ErrorKind::Unsupported => digest::discard(&mut self.reader, len, None)?,
+                    result => result?,
+                },
+                _ => digest::discard(&mut self.reader, len, self.hasher.as_mut())?,
+            };
+            let skipped = skipped_len / sample_len as u64;
+            self.trailing_bytes += usize::try_from(skipped_len % sample_len as u64).unwrap_or(0);
+            self.samples_read += skipped;
+            self.fast_forward(skipped);
+            self.reset_filters();
+            return Ok(from_carry + skipped);
+        }
+        Ok(from_carry)
+    }
+
+    /// Read the next chunk of samples as `Complex<T>`, with the output precision chosen at the call site,
+    /// e.g. `reader.read_next_chunk::<f32>()`.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let mut reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(1024)
+    ///     .sample_type(SampleType::F64)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// let samples = reader.read_next_chunk::<f64>().unwrap();
+    /// ```
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk<T: Float + FromPrimitive>(&mut self) -> Result<Option<Vec<Complex<T>>>, SdrReaderError> {
+        let mut samples = Vec::with_capacity(self.samples_per_chunk);
+        Ok(self.read_next_chunk_into(&mut samples)?.map(|_| samples))
+    }
 
     /// Read the next chunk of samples as Complex<f32> from the file.
     ///
@@ -85,112 +1293,2879 @@ impl SdrFileReader {
     /// - `Ok(None)` if the end of the file is reached
     ///
     /// # Errors
-    /// - `std::io::Error` if there was an error reading the file other than reaching the end
-    pub fn read_next_chunk_complexf32(&mut self) -> Result<Option<Vec<Complex<f32>>>, std::io::Error> {
-        let mut buffer = vec![0u8; self.samples_per_chunk * self.sample_type.sample_len()]; // 2 for I and Q
-        match self.reader.read_exact(&mut buffer) {
-            Ok(()) => {
-                let mut samples = Vec::with_capacity(self.samples_per_chunk);
-                match self.sample_type {
-                    SampleType::U8 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f32::from(s[0]), f32::from(s[1])))),
-                    SampleType::I8 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f32::from(i8::from_ne_bytes([s[0]])), f32::from(i8::from_ne_bytes([s[1]]))))),
-                    SampleType::U16 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f32::from(u16::from_ne_bytes([s[0], s[1]])), f32::from(u16::from_ne_bytes([s[2], s[3]]))))),
-                    SampleType::I16 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f32::from(i16::from_ne_bytes([s[0], s[1]])), f32::from(i16::from_ne_bytes([s[2], s[3]]))))),
-                    SampleType::F32 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f32::from_ne_bytes([s[0], s[1], s[2], s[3]]), f32::from_ne_bytes([s[4], s[5], s[6], s[7]])))),
-                    #[allow(clippy::cast_possible_truncation)]
-                    SampleType::F64 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f64::from_ne_bytes([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]]) as f32, f64::from_ne_bytes([s[8], s[9], s[10], s[11], s[12], s[13], s[14], s[15]]) as f32))),
-                }
-                Ok(Some(samples))
-            }
-            Err(why) => match why.kind() {
-                ErrorKind::UnexpectedEof => Ok(None),
-                _ => Err(why),
-            },
-        }
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_complexf32(&mut self) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        self.read_next_chunk::<f32>()
     }
 
-    /// Read the next chunk of samples as Complex<f64> from the file.
+    /// Read the next chunk of samples as Complex<f32> into `samples`, reusing its allocation.
+    /// The vector is cleared before the new samples are written.
+    ///
+    /// # Warning
+    /// If you have set the sample type to `SampleType::F64`, you should use `read_next_chunk_into_f64` instead. Otherwise, the values will be truncated and you will lose accuracy.
     ///
     /// # Returns
-    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(Some(n))` with the number of samples written if there are samples in the chunk
     /// - `Ok(None)` if the end of the file is reached
     ///
     /// # Errors
-    /// - `std::io::Error` if there was an error reading the file other than reaching the end
-    pub fn read_next_chunk_complexf64(&mut self) -> Result<Option<Vec<Complex<f64>>>, std::io::Error> {
-        let mut buffer = vec![0u8; self.samples_per_chunk * self.sample_type.sample_len()]; // 2 for I and Q
-        match self.reader.read_exact(&mut buffer) {
-            Ok(()) => {
-                let mut samples = Vec::with_capacity(self.samples_per_chunk);
-                match self.sample_type {
-                    SampleType::U8 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f64::from(s[0]), f64::from(s[1])))),
-                    SampleType::I8 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f64::from(i8::from_ne_bytes([s[0]])), f64::from(i8::from_ne_bytes([s[1]]))))),
-                    SampleType::U16 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f64::from(u16::from_ne_bytes([s[0], s[1]])), f64::from(u16::from_ne_bytes([s[2], s[3]]))))),
-                    SampleType::I16 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f64::from(i16::from_ne_bytes([s[0], s[1]])), f64::from(i16::from_ne_bytes([s[2], s[3]]))))),
-                    SampleType::F32 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f64::from(f32::from_ne_bytes([s[0], s[1], s[2], s[3]])), f64::from(f32::from_ne_bytes([s[4], s[5], s[6], s[7]]))))),
-                    SampleType::F64 => buffer.chunks_exact(self.sample_type.sample_len())
-                        .for_each(|s| samples.push(Complex::new(f64::from_ne_bytes([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]]), f64::from_ne_bytes([s[8], s[9], s[10], s[11], s[12], s[13], s[14], s[15]])))),
-                }
-                Ok(Some(samples))
-            }
-            Err(why) => match why.kind() {
-                ErrorKind::UnexpectedEof => Ok(None),
-                _ => Err(why),
-            },
-        }
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_into_f32(&mut self, samples: &mut Vec<Complex<f32>>) -> Result<Option<usize>, SdrReaderError> {
+        self.read_next_chunk_into(samples)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_sdr_file_reader_f32() {
-        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
-        let mut reader = SdrFileReader::builder()
-            .file_path(file_path)
-            .samples_per_chunk(1024)
-            .sample_type(SampleType::F32)
-            .build()
-            .expect("Failed to create SdrFileReader");
-        while let Some(samples) = reader.read_next_chunk_complexf32().unwrap() {
-            assert_eq!(samples.len(), 1024);
-            // Further assertions can be added based on expected values
+    /// Read the next `n` samples as Complex<f32>, independent of `samples_per_chunk`, e.g. for a block size that
+    /// the chunk size doesn't divide. The samples are read in pieces of at most `samples_per_chunk`, so no samples are lost
+    /// between calls, and chunk reads can continue where this stops.
+    /// Fewer than `n` samples are returned at the end of the file, and an empty vector once it is reached.
+    ///
+    /// `n` counts the samples read from the source, before decimation and resampling, like `samples_per_chunk` and
+    /// `max_samples`. With a `decimation_factor` of 4, reading 1000 samples returns 250, and reading fewer samples than
+    /// the decimation factor can return an empty vector before the end of the file.
+    /// The [`TrailingPolicy`] doesn't apply, as the samples aren't read in whole chunks.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `overlap` is set, as the samples would be returned twice
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` if the file ends in the middle of a sample
+    pub fn read_samples_complexf32(&mut self, n: usize) -> Result<Vec<Complex<f32>>, SdrReaderError> {
+        if self.overlap > 0 {
+            return Err(SdrReaderError::InvalidConfig("read_samples_complexf32 can't be combined with overlap".to_string()));
         }
+        let (samples_per_chunk, trailing_policy, pad_final_chunk) = (self.samples_per_chunk, self.trailing_policy, self.pad_final_chunk);
+        (self.trailing_policy, self.pad_final_chunk) = (TrailingPolicy::PartialChunk, false);
+        let mut samples = Vec::with_capacity(n);
+        let mut chunk = std::mem::take(&mut self.scratch);
+        let mut left = n as u64;
+        let result = loop {
+            if left == 0 {
+                break Ok(());
+            }
+            self.samples_per_chunk = usize::try_from(left).unwrap_or(usize::MAX).min(samples_per_chunk);
+            let samples_read = self.samples_read;
+            match self.read_next_chunk_into(&mut chunk) {
+                Ok(Some(_)) => samples.extend_from_slice(&chunk),
+                Ok(None) => break Ok(()),
+                Err(why) => break Err(why),
+            }
+            left = left.saturating_sub(self.samples_read - samples_read);
+        };
+        (self.samples_per_chunk, self.trailing_policy, self.pad_final_chunk) = (samples_per_chunk, trailing_policy, pad_final_chunk);
+        self.scratch = chunk;
+        result.map(|()| samples)
     }
 
-    #[test]
-    fn test_sdr_file_reader_f64() {
-        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
-        let mut reader = SdrFileReader::builder()
-            .file_path(file_path)
-            .samples_per_chunk(1024)
-            .sample_type(SampleType::F32)
-            .build()
-            .expect("Failed to create SdrFileReader");
-        while let Some(samples) = reader.read_next_chunk_complexf64().unwrap() {
-            assert_eq!(samples.len(), 1024);
-            // Further assertions can be added based on expected values
-        }
+    /// Read the raw bytes of the next chunk into the internal buffer and return a borrow of them, to decode them
+    /// without an intermediate `Vec`, e.g. straight into SIMD registers. The borrow ends with the next call on the reader.
+    ///
+    /// The bytes are returned as stored in the file: the decoding options (byte order, normalization, ...) and the
+    /// processing (DC removal, gain, filters, ...) are not applied. The frequency shift and decimation continue as if the
+    /// samples had been read, like with [`SdrFileReader::skip_samples`].
+    /// The length is a multiple of `sample_type.sample_len()`, and the chunk starts with the overlapping samples if `overlap` is set.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let mut reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(1024)
+    ///     .sample_type(SampleType::F32)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// while let Some(bytes) = reader.next_raw_chunk().unwrap() {
+    ///     let first_i = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    /// - `Ok(Some(bytes))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn next_raw_chunk(&mut self) -> Result<Option<&[u8]>, SdrReaderError> {
+        let Some(len) = self.read_chunk_bytes()? else {
+            return Ok(None);
+        };
+        self.fast_forward((len / self.decode.sample_type.sample_len()) as u64);
+        Ok(Some(&self.buffer[..len]))
     }
 
-    #[test]
-    fn test_compare_f32_f64_readings() {
-        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
-        let mut reader_f32 = SdrFileReader::builder()
-            .file_path(file_path)
+    /// Read the next chunk of samples as Complex<f64> from the file.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_complexf64(&mut self) -> Result<Option<Vec<Complex<f64>>>, SdrReaderError> {
+        self.read_next_chunk::<f64>()
+    }
+
+    /// Read the next chunk of samples as Complex<f64> into `samples`, reusing its allocation.
+    /// The vector is cleared before the new samples are written.
+    ///
+    /// # Returns
+    /// - `Ok(Some(n))` with the number of samples written if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_into_f64(&mut self, samples: &mut Vec<Complex<f64>>) -> Result<Option<usize>, SdrReaderError> {
+        self.read_next_chunk_into(samples)
+    }
+
+    /// Read the next chunk of samples as Complex<f32>, together with the time of its first sample
+    /// relative to the start of the capture, see [`SdrFileReader::sample_to_duration`].
+    /// Add the timestamp of the metadata (e.g. parsed from a gqrx file name) to get the absolute time of the chunk.
+    ///
+    /// The time is that of the first raw sample of the chunk, before the decimation and resampling.
+    /// For multi-channel captures it counts the samples of a single channel.
+    ///
+    /// # Returns
+    /// - `Ok(Some((start, samples)))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if the sample rate is not known
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    #[allow(clippy::type_complexity)]
+    pub fn read_next_chunk_timed_f32(&mut self) -> Result<Option<(Duration, Vec<Complex<f32>>)>, SdrReaderError> {
+        // The carried overlap samples have already been counted as read
+        let carried = (self.carry.len() / self.decode.sample_type.sample_len()) as u64;
+        let start = self
+            .sample_to_duration((self.samples_read - carried) / self.channels as u64)
+            .ok_or_else(|| SdrReaderError::InvalidConfig("Timed chunks require a known sample rate".to_string()))?;
+        Ok(self.read_next_chunk_complexf32()?.map(|samples| (start, samples)))
+    }
+
+    /// Read and decode the next chunk into `samples`, applying the DC removal, the frequency shift and the decimation.
+    fn read_next_chunk_into<T: Float + FromPrimitive>(&mut self, samples: &mut Vec<Complex<T>>) -> Result<Option<usize>, SdrReaderError> {
+        samples.clear();
+        let Some(len) = self.read_chunk_bytes()? else {
+            return Ok(None);
+        };
+        self.decode.decode(&self.buffer[..len], samples);
+        self.process(samples);
+        self.pad(samples);
+        Ok(Some(samples.len()))
+    }
+
+    /// Fill a short chunk up to `samples_per_chunk` samples with zeros if `pad_final_chunk` is set,
+    /// and remember the number of zeros.
+    pub(crate) fn pad<T: Float>(&mut self, samples: &mut Vec<Complex<T>>) {
+        let len = self.padded_len(samples.len());
+        samples.resize(len, Complex::new(T::zero(), T::zero()));
+    }
+
+    /// The length of a chunk of `len` samples after padding, which remembers the number of zeros.
+    fn padded_len(&mut self, len: usize) -> usize {
+        self.padded_samples = 0;
+        if self.pad_final_chunk && len < self.samples_per_chunk {
+            self.padded_samples = self.samples_per_chunk - len;
+        }
+        len + self.padded_samples
+    }
+
+    /// Whether the decoded samples are returned unchanged, without any of the processing of [`SdrFileReader::process`].
+    fn is_passthrough(&self) -> bool {
+        !self.remove_dc
+            && self.iq_balancer.is_none()
+            && self.gain.is_none()
+            && self.agc.is_none()
+            && self.shift_step.is_none()
+            && self.lowpass.is_none()
+            && self.decimation_factor == 1
+            && self.resampler.is_none()
+    }
+
+    /// Apply the DC removal, the IQ imbalance correction, the gains, the frequency shift, the decimation and the resampling to a freshly decoded chunk.
+    fn process<T: Float + FromPrimitive>(&mut self, samples: &mut Vec<Complex<T>>) {
+        let keep = self.advance_decimation(samples.len());
+        if self.remove_dc {
+            dsp::remove_dc(samples, &mut self.dc_offset, DC_ALPHA);
+        }
+        if let Some(iq_balancer) = &mut self.iq_balancer {
+            iq_balancer.process(samples);
+        }
+        if let Some(gain) = self.gain {
+            dsp::scale(samples, gain);
+        }
+        if let Some(agc) = &mut self.agc {
+            agc.process(samples);
+        }
+        if let Some(step) = self.shift_step {
+            self.shift_phase = dsp::mix(samples, self.shift_phase, step);
+        }
+        if let Some(lowpass) = &mut self.lowpass {
+            lowpass.process(samples);
+        }
+        if self.decimation_factor > 1 {
+            let mut index = 0;
+            samples.retain(|_| {
+                index += 1;
+                keep(index - 1)
+            });
+        }
+        if let Some(resampler) = &mut self.resampler {
+            resampler.process(samples);
+        }
+    }
+
+    /// Advance the decimation past a chunk of `count` samples.
+    /// Returns whether the sample at an index within that chunk is kept.
+    fn advance_decimation(&mut self, count: usize) -> impl Fn(usize) -> bool {
+        let (skip, n) = (self.decimation_skip, self.decimation_factor);
+        self.decimation_skip = if count > skip { (n - (count - skip) % n) % n } else { skip - count };
+        move |index| index >= skip && (index - skip) % n == 0
+    }
+
+    /// Read the next chunk of samples into `out` as interleaved f32 components: `[i0, q0, i1, q1, ...]`.
+    /// This is the layout many FFT libraries and C APIs expect. The vector is cleared before the new samples are written.
+    ///
+    /// Since `Complex<f32>` is `#[repr(C)]`, a `&[Complex<f32>]` from [`SdrFileReader::read_next_chunk_complexf32`]
+    /// has the same memory layout and could be reinterpreted as a `&[f32]` of twice the length with `unsafe` code.
+    /// This method avoids the need for that by writing the components directly. Without any processing
+    /// (DC removal, IQ imbalance correction, gain, AGC, frequency shift, low-pass filter, decimation or resampling),
+    /// the samples are decoded directly into `out`.
+    ///
+    /// # Returns
+    /// - `Ok(Some(n))` with the number of samples (not components) written if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_interleaved_f32(&mut self, out: &mut Vec<f32>) -> Result<Option<usize>, SdrReaderError> {
+        out.clear();
+        if !self.is_passthrough() {
+            let mut samples = std::mem::take(&mut self.scratch);
+            let result = self.read_next_chunk_into(&mut samples);
+            out.extend(samples.iter().flat_map(|sample| [sample.re, sample.im]));
+            self.scratch = samples;
+            return result;
+        }
+        let Some(len) = self.read_chunk_bytes()? else {
+            return Ok(None);
+        };
+        let count = self.padded_len(len / self.decode.sample_type.sample_len());
+        out.reserve(2 * count);
+        self.decode.decode_with(&self.buffer[..len], |i: f32, q| out.extend([i, q]));
+        out.resize(2 * count, 0.0);
+        Ok(Some(count))
+    }
+
+    /// Read the next chunk of samples as separate vectors of the I and Q components, e.g. for DSP code
+    /// that processes the components separately. The vectors hold the same values as the `re` and `im`
+    /// of [`SdrFileReader::read_next_chunk_complexf32`].
+    /// Without any processing (DC removal, IQ imbalance correction, gain, AGC, frequency shift, low-pass filter,
+    /// decimation or resampling), the samples are decoded directly into the two vectors.
+    ///
+    /// # Returns
+    /// - `Ok(Some((i, q)))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    #[allow(clippy::type_complexity)]
+    pub fn read_next_chunk_split_f32(&mut self) -> Result<Option<(Vec<f32>, Vec<f32>)>, SdrReaderError> {
+        if !self.is_passthrough() {
+            let mut samples = std::mem::take(&mut self.scratch);
+            let result = self.read_next_chunk_into(&mut samples);
+            let split = samples.iter().map(|sample| (sample.re, sample.im)).unzip();
+            self.scratch = samples;
+            return Ok(result?.map(|_| split));
+        }
+        let Some(len) = self.read_chunk_bytes()? else {
+            return Ok(None);
+        };
+        let count = self.padded_len(len / self.decode.sample_type.sample_len());
+        let (mut i, mut q) = (Vec::with_capacity(count), Vec::with_capacity(count));
+        self.decode.decode_with(&self.buffer[..len], |re, im| {
+            i.push(re);
+            q.push(im);
+        });
+        i.resize(count, 0.0);
+        q.resize(count, 0.0);
+        Ok(Some((i, q)))
+    }
+
+    /// Read the next chunk as the instantaneous power `re * re + im * im` of each sample.
+    ///
+    /// # Returns
+    /// - `Ok(Some(power))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_power_f32(&mut self) -> Result<Option<Vec<f32>>, SdrReaderError> {
+        self.read_next_chunk_mapped(Complex::norm_sqr)
+    }
+
+    /// Read the next chunk as the power `re * re + im * im` of each sample, smoothed with a moving average
+    /// over the last `window` samples, e.g. for a steadier power display. The average carries over between chunks,
+    /// so there are no jumps at chunk boundaries. Until `window` samples have been read, the average is over the samples
+    /// read so far. Changing `window` between calls starts a new average, and seeking and skipping reset it.
+    ///
+    /// # Returns
+    /// - `Ok(Some(power))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `window` is 0
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_smoothed_power_f32(&mut self, window: usize) -> Result<Option<Vec<f32>>, SdrReaderError> {
+        if window == 0 {
+            return Err(SdrReaderError::InvalidConfig("The smoothing window must not be 0".to_string()));
+        }
+        if self.smoother.as_ref().is_none_or(|smoother| smoother.window() != window) {
+            self.smoother = Some(MovingAverage::new(window));
+        }
+        let Some(mut power) = self.read_next_chunk_power_f32()? else {
+            return Ok(None);
+        };
+        if let Some(smoother) = &mut self.smoother {
+            smoother.process(&mut power);
+        }
+        Ok(Some(power))
+    }
+
+    /// Read the next chunk as the magnitude `sqrt(re * re + im * im)` of each sample.
+    ///
+    /// # Returns
+    /// - `Ok(Some(magnitude))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_magnitude_f32(&mut self) -> Result<Option<Vec<f32>>, SdrReaderError> {
+        self.read_next_chunk_mapped(|sample| sample.norm())
+    }
+
+    /// Read the next chunk as the instantaneous phase `atan2(im, re)` of each sample, in radians in the range [-π, π].
+    ///
+    /// # Returns
+    /// - `Ok(Some(phase))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_phase_f32(&mut self) -> Result<Option<Vec<f32>>, SdrReaderError> {
+        self.read_next_chunk_mapped(|sample| sample.arg())
+    }
+
+    /// Read the next chunk as the phase difference between each sample and the one before it, in radians in the range [-π, π].
+    /// This is the instantaneous frequency in radians per sample, i.e. the output of a simple FM demodulator.
+    /// The last sample is kept between calls, so the first difference of a chunk is taken to the last sample of the previous chunk.
+    /// The first difference after building, seeking or resetting the reader is 0.
+    ///
+    /// # Returns
+    /// - `Ok(Some(differences))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_phase_diff_f32(&mut self) -> Result<Option<Vec<f32>>, SdrReaderError> {
+        let mut samples = std::mem::take(&mut self.scratch);
+        let result = self.read_next_chunk_into(&mut samples);
+        let differences = samples
+            .iter()
+            .map(|sample| {
+                let previous = self.previous_sample.replace(*sample).unwrap_or(*sample);
+                (sample * previous.conj()).arg()
+            })
+            .collect();
+        self.scratch = samples;
+        Ok(result?.map(|_| differences))
+    }
+
+    /// Read only the I or only the Q component of each sample in the next chunk, as real values.
+    /// Useful for recordings with a real signal in one channel, or to analyze I and Q independently.
+    /// The component is taken after `swap_iq`, `conjugate` and the other processing steps.
+    ///
+    /// # Returns
+    /// - `Ok(Some(values))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_real_f32(&mut self, component: Component) -> Result<Option<Vec<f32>>, SdrReaderError> {
+        match component {
+            Component::I => self.read_next_chunk_mapped(|sample| sample.re),
+            Component::Q => self.read_next_chunk_mapped(|sample| sample.im),
+        }
+    }
+
+    /// Read the next chunk of a multi-channel recording and return only the samples of `channel` as Complex<f32>.
+    /// The chunk holds `samples_per_chunk / channels` samples of each channel, see the `channels` option.
+    /// The samples of the other channels in the chunk are dropped, so reading all channels takes one reader per channel.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `channel` is not less than `channels`
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_next_chunk_channel_f32(&mut self, channel: usize) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        if channel >= self.channels {
+            return Err(SdrReaderError::InvalidConfig(format!("channel {channel} is out of range for {} channels", self.channels)));
+        }
+        let mut samples = std::mem::take(&mut self.scratch);
+        let result = self.read_next_chunk_into(&mut samples);
+        let channel_samples = samples.iter().skip(channel).step_by(self.channels).copied().collect();
+        self.scratch = samples;
+        Ok(result?.map(|_| channel_samples))
+    }
+
+    /// Read the next chunk and map each sample to a single value.
+    /// The decoded samples only live in the reused scratch buffer, so no `Complex` vector is allocated.
+    fn read_next_chunk_mapped(&mut self, map: impl Fn(&Complex<f32>) -> f32) -> Result<Option<Vec<f32>>, SdrReaderError> {
+        let mut samples = std::mem::take(&mut self.scratch);
+        let result = self.read_next_chunk_into(&mut samples);
+        let values = samples.iter().map(map).collect();
+        self.scratch = samples;
+        Ok(result?.map(|_| values))
+    }
+
+    /// Read chunks until one is not silent, see [`chunk_is_silent`], and return it. The silent chunks are dropped.
+    /// The threshold applies to the samples as returned, so it depends on `normalize` for integer sample types.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` with the first chunk whose mean power is at least `threshold`
+    /// - `Ok(None)` if the end of the file is reached before any such chunk
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn skip_silent_chunks(&mut self, threshold: f32) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        let mut samples = Vec::with_capacity(self.samples_per_chunk);
+        while self.read_next_chunk_into(&mut samples)?.is_some() {
+            if !chunk_is_silent(&samples, threshold) {
+                return Ok(Some(samples));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read all remaining chunks of the file and call `f` with each sample as Complex<f32>.
+    /// The samples are decoded into a buffer that is reused for every chunk, so nothing is allocated per chunk,
+    /// which makes this the fastest way to fold over or stream a whole file.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let mut reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(1024)
+    ///     .sample_type(SampleType::F32)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// let mut energy = 0.0;
+    /// reader.for_each_sample_f32(|sample| energy += sample.norm_sqr()).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn for_each_sample_f32<F: FnMut(Complex<f32>)>(&mut self, mut f: F) -> Result<(), SdrReaderError> {
+        let mut samples = std::mem::take(&mut self.scratch);
+        let result = loop {
+            match self.read_next_chunk_into(&mut samples) {
+                Ok(Some(_)) => samples.iter().copied().for_each(&mut f),
+                Ok(None) => break Ok(()),
+                Err(why) => break Err(why),
+            }
+        };
+        self.scratch = samples;
+        result
+    }
+
+    /// Read all remaining chunks of the file and return the fraction of samples whose magnitude is above `threshold`,
+    /// see [`count_above_threshold`], e.g. to estimate how much of the time a channel is occupied.
+    /// Returns 0.0 if there are no samples left.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn occupancy(&mut self, threshold: f32) -> Result<f32, SdrReaderError> {
+        let mut samples = std::mem::take(&mut self.scratch);
+        let (mut above, mut total) = (0usize, 0usize);
+        let result = loop {
+            match self.read_next_chunk_into(&mut samples) {
+                Ok(Some(_)) => {
+                    above += count_above_threshold(&samples, threshold);
+                    total += samples.len();
+                }
+                Ok(None) => break Ok(()),
+                Err(why) => break Err(why),
+            }
+        };
+        self.scratch = samples;
+        result?;
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        Ok(if total == 0 { 0.0 } else { (above as f64 / total as f64) as f32 })
+    }
+
+    /// Iterate over the remaining chunks of the file as Complex<f32>.
+    ///
+    /// This wraps [`SdrFileReader::read_next_chunk_complexf32`], so the same warning about `SampleType::F64` applies.
+    /// The iterator ends when the end of the file is reached and yields `Some(Err(..))` on a read error.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let mut reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(1024)
+    ///     .sample_type(SampleType::F32)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// for chunk in reader.chunks_complexf32().take(4) {
+    ///     let samples = chunk.unwrap();
+    /// }
+    /// ```
+    pub fn chunks_complexf32(&mut self) -> impl Iterator<Item = Result<Vec<Complex<f32>>, SdrReaderError>> + '_ {
+        std::iter::from_fn(move || self.read_next_chunk_complexf32().transpose())
+    }
+
+    /// Iterate over the remaining chunks of the file as Complex<f64>.
+    ///
+    /// This wraps [`SdrFileReader::read_next_chunk_complexf64`].
+    /// The iterator ends when the end of the file is reached and yields `Some(Err(..))` on a read error.
+    pub fn chunks_complexf64(&mut self) -> impl Iterator<Item = Result<Vec<Complex<f64>>, SdrReaderError>> + '_ {
+        std::iter::from_fn(move || self.read_next_chunk_complexf64().transpose())
+    }
+
+    /// Iterate over the remaining samples of the file one at a time as Complex<f32>.
+    ///
+    /// The samples are still read chunk by chunk internally, but the chunking is hidden,
+    /// so adapters like `take`, `step_by` or `skip` work on individual samples.
+    /// A read error is yielded once as `Some(Err(..))` in place of the samples of the failed chunk.
+    /// Dropping the iterator discards the samples of the current chunk that haven't been yielded yet.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let mut reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(1024)
+    ///     .sample_type(SampleType::F32)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// let every_tenth = reader.samples_complexf32().step_by(10).take(100).collect::<Result<Vec<_>, _>>().unwrap();
+    /// ```
+    pub fn samples_complexf32(&mut self) -> impl Iterator<Item = Result<Complex<f32>, SdrReaderError>> + '_ {
+        self.chunks_complexf32().flat_map(flatten_chunk)
+    }
+
+    /// Iterate over the remaining samples of the file one at a time as Complex<f64>.
+    ///
+    /// This is the Complex<f64> counterpart of [`SdrFileReader::samples_complexf32`].
+    pub fn samples_complexf64(&mut self) -> impl Iterator<Item = Result<Complex<f64>, SdrReaderError>> + '_ {
+        self.chunks_complexf64().flat_map(flatten_chunk)
+    }
+}
+
+/// Turn a chunk read result into the results of its individual samples, or a single error.
+#[cfg(feature = "std")]
+fn flatten_chunk<T>(chunk: Result<Vec<T>, SdrReaderError>) -> impl Iterator<Item = Result<T, SdrReaderError>> {
+    let (samples, error) = match chunk {
+        Ok(samples) => (samples, None),
+        Err(why) => (Vec::new(), Some(Err(why))),
+    };
+    samples.into_iter().map(Ok).chain(error)
+}
+
+#[cfg(feature = "std")]
+impl<R: Read + Seek> SdrFileReader<R> {
+    /// Let [`SdrFileReader::advance_samples`] seek over the skipped bytes instead of reading them.
+    pub(crate) fn with_seek(mut self) -> Self {
+        self.seek_forward = Some(Self::seek_forward);
+        self
+    }
+
+    /// Seek up to `len` bytes forward, but not past the end of the stream, and return how far it went.
+    fn seek_forward(reader: &mut BufReader<R>, len: u64) -> std::io::Result<u64> {
+        let position = reader.stream_position()?;
+        let inner = reader.get_mut();
+        let inner_position = inner.stream_position()?;
+        let end = inner.seek(SeekFrom::End(0))?;
+        inner.seek(SeekFrom::Start(inner_position))?;
+        let skip = len.min(end.saturating_sub(position));
+        reader.seek_relative(i64::try_from(skip).unwrap_or(i64::MAX))?;
+        Ok(skip)
+    }
+
+    /// The length of the underlying stream in bytes.
+    /// The inner reader is seeked back to where it was, so the `BufReader` buffer stays valid.
+    fn stream_len(&mut self) -> Result<u64, std::io::Error> {
+        let inner = self.reader.get_mut();
+        let position = inner.stream_position()?;
+        let len = inner.seek(SeekFrom::End(0))?;
+        if position != len {
+            inner.seek(SeekFrom::Start(position))?;
+        }
+        Ok(len)
+    }
+
+    /// The total number of samples in the file.
+    /// Trailing bytes that don't make up a whole sample are not counted.
+    /// This does not change the current read position.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the length of the file could not be determined
+    ///   (e.g. of kind `Unsupported` for gzip-compressed files, which can't be seeked)
+    pub fn total_samples(&mut self) -> Result<u64, SdrReaderError> {
+        Ok(self.stream_len()?.saturating_sub(self.header_len) / self.decode.sample_type.sample_len() as u64)
+    }
+
+    /// The number of samples left to read from the current position to the end of the file.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the length of the file or the current position could not be determined
+    ///   (e.g. of kind `Unsupported` for gzip-compressed files, which can't be seeked)
+    pub fn remaining_samples(&mut self) -> Result<u64, SdrReaderError> {
+        Ok(self.bytes_remaining()? / self.decode.sample_type.sample_len() as u64)
+    }
+
+    /// The number of bytes left from the current position to the end of the file,
+    /// e.g. to size a buffer for all remaining samples.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the length of the file or the current position could not be determined
+    ///   (e.g. of kind `Unsupported` for gzip-compressed files, which can't be seeked)
+    pub fn bytes_remaining(&mut self) -> Result<u64, SdrReaderError> {
+        let file_len = self.stream_len()?;
+        let position = self.reader.stream_position()?;
+        Ok(file_len.saturating_sub(position))
+    }
+
+    /// A heuristic check whether the samples are more likely 32-bit floats than integers, to catch a `sample_type`
+    /// of `SampleType::I16` for an F32 capture or the other way around. The first 4096 bytes after the header are
+    /// interpreted as f32 in the configured byte order: float data gives finite values of moderate magnitude with
+    /// fractional parts, while integer data mostly gives tiny, huge or NaN values.
+    /// Zero words fit both and are ignored, so an all-zero block returns `false`.
+    /// The read position is restored afterwards.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file or the source can't be seeked
+    ///   (of kind `Unsupported` for gzip-compressed files)
+    pub fn looks_like_float(&mut self) -> Result<bool, SdrReaderError> {
+        const BLOCK_LEN: u64 = 4096;
+        let header_len = self.header_len;
+        let inner = self.reader.get_mut();
+        let position = inner.stream_position()?;
+        inner.seek(SeekFrom::Start(header_len))?;
+        let mut block = Vec::new();
+        let result = inner.by_ref().take(BLOCK_LEN).read_to_end(&mut block);
+        inner.seek(SeekFrom::Start(position))?;
+        result?;
+
+        let (mut nonzero, mut plausible, mut fractional) = (0usize, 0usize, 0usize);
+        for word in block.chunks_exact(4) {
+            let value = self.decode.endianness.f32_from_bytes([word[0], word[1], word[2], word[3]]);
+            if value == 0.0 {
+                continue;
+            }
+            nonzero += 1;
+            if value.is_finite() && (1e-8..=1e8).contains(&value.abs()) {
+                plausible += 1;
+                if value.fract() != 0.0 {
+                    fractional += 1;
+                }
+            }
+        }
+        // At least 90% of the words must be plausible floats, and most of those must not be whole numbers
+        Ok(nonzero > 0 && plausible * 10 >= nonzero * 9 && fractional * 2 > plausible)
+    }
+
+    /// The current read position in bytes from the start of the file.
+    /// Bytes the `BufReader` has buffered ahead but not handed out yet are not counted.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the position could not be determined
+    ///   (e.g. of kind `Unsupported` for gzip-compressed files, which can't be seeked)
+    pub fn byte_position(&mut self) -> Result<u64, SdrReaderError> {
+        Ok(self.reader.stream_position()?)
+    }
+
+    /// The index of the next sample that will be read, counted from the first sample after the header.
+    /// With `overlap` set, this is the first overlapping sample the next chunk starts with.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the position could not be determined
+    ///   (e.g. of kind `Unsupported` for gzip-compressed files, which can't be seeked)
+    pub fn sample_position(&mut self) -> Result<u64, SdrReaderError> {
+        let position = self.byte_position()?.saturating_sub(self.header_len + self.carry.len() as u64);
+        Ok(position / self.decode.sample_type.sample_len() as u64)
+    }
+
+    /// Check that the length of the file after the header is a whole number of samples.
+    /// A leftover of a few bytes usually means the wrong `SampleType` was chosen, or the file was truncated.
+    /// This does not change the current read position.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::UnexpectedPartialSample` with the number of leftover bytes if the length is not a multiple of `sample_type.sample_len()`
+    /// - `SdrReaderError::Io` if the length of the file could not be determined
+    ///   (e.g. of kind `Unsupported` for gzip-compressed files, which can't be seeked)
+    pub fn validate(&mut self) -> Result<(), SdrReaderError> {
+        let bytes_left = self.stream_len()?.saturating_sub(self.header_len) % self.decode.sample_type.sample_len() as u64;
+        match usize::try_from(bytes_left).unwrap_or(usize::MAX) {
+            0 => Ok(()),
+            bytes_left => Err(SdrReaderError::UnexpectedPartialSample { bytes_left }),
+        }
+    }
+
+    /// Move the read position to the sample at index `sample`, counted from the first sample after the header.
+    ///
+    /// The frequency shift and decimation continue as if the file had been read linearly up to `sample`,
+    /// so the chunks read after seeking are the same as those of a linear read. `max_samples` counts from
+    /// the start of the file as well. The DC offset and IQ imbalance estimates and the AGC gain are kept.
+    /// Seeking past the end of the file is allowed, the next read then returns `Ok(None)`.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the source can't be seeked (of kind `Unsupported` for gzip-compressed files)
+    pub fn seek_to_sample(&mut self, sample: u64) -> Result<(), SdrReaderError> {
+        let sample_len = self.decode.sample_type.sample_len() as u64;
+        self.reader.seek(SeekFrom::Start(sample.saturating_mul(sample_len).saturating_add(self.header_len)))?;
+        self.samples_read = sample;
+        self.trailing_bytes = 0;
+        self.carry = 0..0;
+        self.previous_sample = None;
+        self.reset_filters();
+        let n = self.decimation_factor as u64;
+        self.decimation_skip = usize::try_from((n - sample % n) % n).unwrap_or(0);
+        if let Some(step) = self.shift_step {
+            #[allow(clippy::cast_precision_loss)]
+            let phase = (sample as f64 * step).rem_euclid(TAU);
+            self.shift_phase = phase;
+        }
+        Ok(())
+    }
+
+    /// Move the read position to the sample at `time` after the start of the capture, rounded down to a whole sample,
+    /// e.g. `Duration::from_millis(3500)` to jump to 00:03.5. Otherwise the same as [`SdrFileReader::seek_to_sample`].
+    /// Seeking to the end of the capture is allowed, the next read then returns `Ok(None)`.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if the sample rate is not known
+    /// - `SdrReaderError::OutOfRange` if `time` is past the end of the capture
+    /// - `SdrReaderError::Io` if the length of the file could not be determined or the source can't be seeked
+    ///   (of kind `Unsupported` for gzip-compressed files)
+    pub fn seek_to_time(&mut self, time: Duration) -> Result<(), SdrReaderError> {
+        if self.sample_rate_hz.is_none_or(|rate| rate == 0) {
+            return Err(SdrReaderError::InvalidConfig("seek_to_time requires a nonzero sample_rate_hz".to_string()));
+        }
+        let total_samples = self.total_samples()?;
+        match self.duration_to_sample(time) {
+            Some(sample) if sample <= total_samples => self.seek_to_sample(sample),
+            _ => Err(SdrReaderError::OutOfRange(format!("{time:?} is past the end of the capture ({total_samples} samples)"))),
+        }
+    }
+
+    /// Rewind to the first sample after the header and reset the state of the DC removal, IQ imbalance correction, AGC,
+    /// frequency shift and decimation,
+    /// so the file can be processed again exactly like after building the reader.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the source can't be seeked (of kind `Unsupported` for gzip-compressed files)
+    pub fn reset(&mut self) -> Result<(), SdrReaderError> {
+        self.seek_to_sample(0)?;
+        self.dc_offset = Complex::new(0.0, 0.0);
+        if let Some(iq_balancer) = &mut self.iq_balancer {
+            *iq_balancer = IqBalancer::default();
+        }
+        if let Some(agc) = &mut self.agc {
+            agc.reset();
+        }
+        Ok(())
+    }
+
+    /// Seek to the chunk at index `chunk_index` and read it as Complex<f32>.
+    /// The chunk starts at sample `chunk_index * samples_per_chunk`, so reading every nth chunk
+    /// (e.g. for the rows of a waterfall) is a loop over `read_chunk_at(row * n)`.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the chunk lies past the end of the file
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end,
+    ///   or the source can't be seeked (of kind `Unsupported` for gzip-compressed files)
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_chunk_at(&mut self, chunk_index: u64) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        self.seek_to_sample(chunk_index.saturating_mul(self.samples_per_chunk as u64))?;
+        self.read_next_chunk_complexf32()
+    }
+
+    /// Guess the byte order of a float recording when it is unknown.
+    /// The next few thousand samples are decoded as both little-endian and big-endian, and the byte order
+    /// in which more components are plausible (finite, and zero or between 1e-10 and 1e10 in magnitude) is returned.
+    /// With the wrong byte order, real IQ data mostly turns into denormal, huge or NaN values. Ties return `Little`.
+    /// The read position is restored afterward, and the configured byte order is not changed.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if the sample type is not `F32` or `F64`
+    /// - `SdrReaderError::Io` if there was an error reading the file, or the source can't be seeked
+    ///   (of kind `Unsupported` for gzip-compressed files)
+    pub fn detect_endianness(&mut self) -> Result<Endianness, SdrReaderError> {
+        /// The number of samples the guess is based on
+        const DETECT_SAMPLES: u64 = 4096;
+        let sample_type = self.decode.sample_type;
+        if !matches!(sample_type, SampleType::F32 | SampleType::F64) {
+            return Err(SdrReaderError::InvalidConfig("Byte order detection only works for F32 and F64 samples".to_string()));
+        }
+        let mut bytes = Vec::new();
+        (&mut self.reader).take(DETECT_SAMPLES * sample_type.sample_len() as u64).read_to_end(&mut bytes)?;
+        self.reader.seek_relative(-i64::try_from(bytes.len()).unwrap_or(i64::MAX))?;
+
+        let score = |endianness: Endianness| {
+            decode_into::<f64>(&bytes, &sample_type, &endianness)
+                .iter()
+                .flat_map(|sample| [sample.re.abs(), sample.im.abs()])
+                .filter(|value| *value == 0.0 || (1e-10..=1e10).contains(value))
+                .count()
+        };
+        Ok(if score(Endianness::Big) > score(Endianness::Little) { Endianness::Big } else { Endianness::Little })
+    }
+
+    /// Estimate the noise floor in dB from the power of the next `sample_blocks * samples_per_chunk` samples,
+    /// e.g. to set the threshold of [`SdrFileReader::occupancy`] or [`SdrFileReader::skip_silent_chunks`] automatically.
+    /// The estimate is the 10th percentile of the power of the samples, scaled to the mean power of complex Gaussian
+    /// noise with the same percentile, so signals in up to a few percent of the samples hardly change it.
+    /// The power is that of the decoded samples (with the centering, normalization and I/Q options),
+    /// before the DC removal, gain and filters. Returns `f32::NEG_INFINITY` if there are no samples left.
+    /// The read position is restored afterward.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `sample_blocks` is 0
+    /// - `SdrReaderError::Io` if there was an error reading the file, or the source can't be seeked
+    ///   (of kind `Unsupported` for gzip-compressed files)
+    pub fn estimate_noise_floor_db(&mut self, sample_blocks: usize) -> Result<f32, SdrReaderError> {
+        /// The percentile of the power the estimate is based on
+        const PERCENTILE: f64 = 0.1;
+        if sample_blocks == 0 {
+            return Err(SdrReaderError::InvalidConfig("sample_blocks must be greater than 0".to_string()));
+        }
+        let sample_len = self.decode.sample_type.sample_len();
+        let len = sample_blocks.saturating_mul(self.bytes_per_chunk());
+        let mut bytes = Vec::new();
+        (&mut self.reader).take(len as u64).read_to_end(&mut bytes)?;
+        self.reader.seek_relative(-i64::try_from(bytes.len()).unwrap_or(i64::MAX))?;
+
+        let mut power = Vec::with_capacity(bytes.len() / sample_len);
+        self.decode.decode_with(&bytes[..bytes.len() - bytes.len() % sample_len], |i: f64, q: f64| power.push(i * i + q * q));
+        if power.is_empty() {
+            return Ok(f32::NEG_INFINITY);
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss, clippy::cast_sign_loss)]
+        let index = ((power.len() - 1) as f64 * PERCENTILE) as usize;
+        let (_, percentile, _) = power.select_nth_unstable_by(index, f64::total_cmp);
+        // The power of complex Gaussian noise is exponentially distributed, with the percentile p at -ln(1 - p) times the mean
+        let mean = *percentile / -(1.0 - PERCENTILE).ln();
+        #[allow(clippy::cast_possible_truncation)]
+        Ok((10.0 * mean.log10()) as f32)
+    }
+
+    /// Read the next chunk of samples as Complex<f32> without advancing the read position,
+    /// so the following [`SdrFileReader::read_next_chunk_complexf32`] returns the same samples.
+    /// The state of the DC removal, IQ imbalance correction, AGC, frequency shift, decimation and resampling is restored as well.
+    ///
+    /// Only available for seekable sources. Gzip-compressed files can't be seeked, so peeking into them fails.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end,
+    ///   or the source can't be seeked (of kind `Unsupported` for gzip-compressed files)
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn peek_next_chunk_complexf32(&mut self) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        let position = self.reader.stream_position()?;
+        let state = (self.samples_read, self.trailing_bytes, self.decimation_skip, self.shift_phase, self.dc_offset, self.padded_samples);
+        let (carried, lowpass, resampler, hasher) = (self.carry.len(), self.lowpass.clone(), self.resampler.clone(), self.hasher.clone());
+        let (iq_balancer, agc) = (self.iq_balancer.clone(), self.agc.clone());
+        let result = self.read_next_chunk_complexf32();
+        (self.samples_read, self.trailing_bytes, self.decimation_skip, self.shift_phase, self.dc_offset, self.padded_samples) = state;
+        (self.lowpass, self.resampler, self.hasher, self.iq_balancer, self.agc) = (lowpass, resampler, hasher, iq_balancer, agc);
+        // The overlapping bytes of the previous chunk were moved to the front of the buffer
+        self.carry = 0..carried;
+        let consumed = self.reader.stream_position()? - position;
+        // Seeking relative to the current position keeps the buffered bytes
+        self.reader.seek_relative(-i64::try_from(consumed).unwrap_or(i64::MAX))?;
+        result
+    }
+
+    /// Read all remaining chunks of the file and concatenate them into one vector of Complex<f32>.
+    /// The result is the same as concatenating the chunks of [`SdrFileReader::chunks_complexf32`],
+    /// so the trailing samples are only included with `TrailingPolicy::PartialChunk`.
+    ///
+    /// The whole file is held in memory: 8 bytes per sample, e.g. 8 GB for 1 billion samples.
+    /// Prefer reading chunk by chunk for large files.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_all_complexf32(&mut self) -> Result<Vec<Complex<f32>>, SdrReaderError> {
+        self.read_all()
+    }
+
+    /// Read all remaining chunks of the file and concatenate them into one vector of Complex<f64>.
+    /// The result is the same as concatenating the chunks of [`SdrFileReader::chunks_complexf64`],
+    /// so the trailing samples are only included with `TrailingPolicy::PartialChunk`.
+    ///
+    /// The whole file is held in memory: 16 bytes per sample, e.g. 16 GB for 1 billion samples.
+    /// Prefer reading chunk by chunk for large files.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`]
+    pub fn read_all_complexf64(&mut self) -> Result<Vec<Complex<f64>>, SdrReaderError> {
+        self.read_all()
+    }
+
+    fn read_all<T: Float + FromPrimitive>(&mut self) -> Result<Vec<Complex<T>>, SdrReaderError> {
+        // Preallocate for the remaining samples if the stream can tell, e.g. not for gzip-compressed files
+        let mut remaining = self.remaining_samples().unwrap_or(0);
+        if let Some(max_samples) = self.max_samples {
+            remaining = remaining.min(max_samples.saturating_sub(self.samples_read));
+        }
+        let capacity = usize::try_from(remaining).unwrap_or(0).div_ceil(self.decimation_factor);
+        let mut all_samples = Vec::with_capacity(capacity);
+        let mut samples = Vec::with_capacity(self.samples_per_chunk);
+        while self.read_next_chunk_into(&mut samples)?.is_some() {
+            all_samples.extend_from_slice(&samples);
+        }
+        Ok(all_samples)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Write `bytes` to a file in the temp directory and return its path.
+    fn write_temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("sdr_iq_file_reader_{name}.raw"));
+        std::fs::write(&path, bytes).expect("Failed to write temp file");
+        path
+    }
+
+    /// A builder for a reader of the `F32` gqrx recording in the repository, with chunks of `samples_per_chunk` samples.
+    fn fixture_reader(samples_per_chunk: usize) -> SdrFileReaderFromReaderBuilder<std::fs::File> {
+        let file = std::fs::File::open("gqrx_20240929_015218_580206500_2400000_fc.raw").expect("Failed to open the fixture");
+        SdrFileReader::from_reader(file, samples_per_chunk, SampleType::F32)
+    }
+
+    #[test]
+    fn test_sdr_file_reader_f32() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        while let Some(samples) = reader.read_next_chunk_complexf32().unwrap() {
+            assert_eq!(samples.len(), 1024);
+            // Further assertions can be added based on expected values
+        }
+    }
+
+    #[test]
+    fn test_sdr_file_reader_f64() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        while let Some(samples) = reader.read_next_chunk_complexf64().unwrap() {
+            assert_eq!(samples.len(), 1024);
+            // Further assertions can be added based on expected values
+        }
+    }
+
+    #[test]
+    fn test_big_endian_i16() {
+        // I = 0x0102 = 258, Q = 0xFF00 = -256
+        let file_path = write_temp_file("big_endian_i16", &[0x01, 0x02, 0xFF, 0x00]);
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::I16)
+            .endianness(Endianness::Big)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(258.0, -256.0)]);
+    }
+
+    #[test]
+    fn test_big_endian_f32() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&1.5f32.to_be_bytes());
+        bytes.extend_from_slice(&(-0.25f32).to_be_bytes());
+        let file_path = write_temp_file("big_endian_f32", &bytes);
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::F32)
+            .endianness(Endianness::Big)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.read_next_chunk_complexf64().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(1.5, -0.25)]);
+    }
+
+    #[test]
+    fn test_default_endianness_is_little() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&1.5f64.to_le_bytes());
+        bytes.extend_from_slice(&(-0.25f64).to_le_bytes());
+        let file_path = write_temp_file("default_endianness", &bytes);
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::F64)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.read_next_chunk_complexf64().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(1.5, -0.25)]);
+    }
+
+    #[test]
+    fn test_center_unsigned_u8() {
+        let file_path = write_temp_file("center_unsigned_u8", &[128, 127]);
+        let build = |center_unsigned| SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::U8)
+            .center_unsigned(center_unsigned)
+            .build()
+            .expect("Failed to create SdrFileReader");
+
+        let samples = build(true).read_next_chunk_complexf32().unwrap().unwrap();
+        assert!((samples[0].re - 0.5).abs() < f32::EPSILON);
+        assert!((samples[0].im + 0.5).abs() < f32::EPSILON);
+
+        let samples = build(false).read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(128.0, 127.0)]);
+    }
+
+    #[test]
+    fn test_center_unsigned_u16() {
+        let file_path = write_temp_file("center_unsigned_u16", &[0x00, 0x80, 0xFF, 0xFF]);
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::U16)
+            .center_unsigned(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.read_next_chunk_complexf64().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(0.5, 32767.5)]);
+    }
+
+    #[test]
+    fn test_normalize_i16() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&32767i16.to_le_bytes());
+        bytes.extend_from_slice(&i16::MIN.to_le_bytes());
+        let file_path = write_temp_file("normalize_i16", &bytes);
+        let build = || SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::I16)
+            .normalize(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+
+        let samples = build().read_next_chunk_complexf32().unwrap().unwrap();
+        assert!((samples[0].re - 1.0).abs() < 1e-4);
+        assert!((samples[0].im + 1.0).abs() < f32::EPSILON);
+
+        let samples = build().read_next_chunk_complexf64().unwrap().unwrap();
+        assert!((samples[0].re - 1.0).abs() < 1e-4);
+        assert!((samples[0].im + 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_normalize_centered_u8() {
+        let file_path = write_temp_file("normalize_centered_u8", &[255, 0]);
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::U8)
+            .center_unsigned(true)
+            .normalize(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.read_next_chunk_complexf64().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(1.0, -1.0)]);
+    }
+
+    #[test]
+    fn test_normalize_passes_floats_through() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&2.5f32.to_le_bytes());
+        bytes.extend_from_slice(&(-3.0f32).to_le_bytes());
+        let file_path = write_temp_file("normalize_f32", &bytes);
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::F32)
+            .normalize(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(2.5, -3.0)]);
+    }
+
+    #[test]
+    fn test_total_and_remaining_samples() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let expected_samples = std::fs::metadata(file_path).unwrap().len() / SampleType::F32.sample_len() as u64;
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.total_samples().unwrap(), expected_samples);
+        assert_eq!(reader.remaining_samples().unwrap(), expected_samples);
+
+        let first_chunk = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(reader.remaining_samples().unwrap(), expected_samples - 2 * 1024);
+        assert_eq!(reader.total_samples().unwrap(), expected_samples);
+
+        // Computing the totals must not move the read cursor
+        let mut fresh_reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        fresh_reader.total_samples().unwrap();
+        assert_eq!(fresh_reader.read_next_chunk_complexf32().unwrap().unwrap(), first_chunk);
+    }
+
+    #[test]
+    fn test_partial_final_chunk() {
+        // 10 I16 samples plus one stray byte
+        let bytes = (0..41).collect::<Vec<u8>>();
+        let file_path = write_temp_file("partial_final_chunk", &bytes);
+        let build = |trailing_bytes| SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(4)
+            .sample_type(SampleType::I16)
+            .trailing_bytes(trailing_bytes)
+            .build()
+            .expect("Failed to create SdrFileReader");
+
+        let chunks = build(TrailingPolicy::PartialChunk).chunks_complexf32().collect::<Vec<_>>();
+        let chunk_lens = chunks[..3].iter().map(|chunk| chunk.as_ref().unwrap().len()).collect::<Vec<_>>();
+        assert_eq!(chunk_lens, vec![4, 4, 2]);
+        assert!(matches!(chunks[3], Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 1 })));
+        assert_eq!(chunks.len(), 4);
+        let mut reader = build(TrailingPolicy::PartialChunk);
+        let last_chunk = reader.chunks_complexf64().nth(2).unwrap().unwrap();
+        assert_eq!(last_chunk[1], Complex::new(f64::from(i16::from_le_bytes([36, 37])), f64::from(i16::from_le_bytes([38, 39]))));
+
+        let chunk_lens = build(TrailingPolicy::Drop).chunks_complexf32().map(|chunk| chunk.unwrap().len()).collect::<Vec<_>>();
+        assert_eq!(chunk_lens, vec![4, 4]);
+    }
+
+    #[test]
+    fn test_unexpected_partial_sample() {
+        // Less than one F32 sample
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0u8; 5]), 4, SampleType::F32)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert!(matches!(
+            reader.read_next_chunk_complexf32(),
+            Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 5 })
+        ));
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+
+        // A whole number of samples is not an error
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0u8; 16]), 4, SampleType::F32)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().map(|chunk| chunk.len()), Some(2));
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+    }
+
+    #[test]
+    fn test_trailing_policy() {
+        // 10 I16 samples plus one stray byte
+        let bytes = (0..41).collect::<Vec<u8>>();
+        // The lengths of the chunks read until the end, and the error at the end
+        let read = |trailing_bytes| {
+            let mut reader = SdrFileReader::from_bytes(bytes.clone(), 4, SampleType::I16).trailing_bytes(trailing_bytes).build().unwrap();
+            let mut chunk_lens = Vec::new();
+            loop {
+                match reader.read_next_chunk_complexf32() {
+                    Ok(Some(chunk)) => chunk_lens.push(chunk.len()),
+                    Ok(None) => return (chunk_lens, None),
+                    Err(why) => return (chunk_lens, Some(why)),
+                }
+            }
+        };
+        let (chunk_lens, error) = read(TrailingPolicy::Drop);
+        assert_eq!(chunk_lens, vec![4, 4]);
+        assert!(error.is_none());
+        let (chunk_lens, error) = read(TrailingPolicy::PartialChunk);
+        assert_eq!(chunk_lens, vec![4, 4, 2]);
+        assert!(matches!(error, Some(SdrReaderError::UnexpectedPartialSample { bytes_left: 1 })));
+        let (chunk_lens, error) = read(TrailingPolicy::Error);
+        assert_eq!(chunk_lens, vec![4, 4]);
+        assert!(matches!(error, Some(SdrReaderError::UnexpectedPartialChunk { bytes_left: 9 })));
+    }
+
+    #[test]
+    fn test_pad_final_chunk() {
+        // 10 I16 samples, so the final chunk of 4 has only 2 samples
+        let bytes = (1..=40).collect::<Vec<u8>>();
+        let build = |trailing_bytes| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 4, SampleType::I16)
+            .trailing_bytes(trailing_bytes)
+            .pad_final_chunk(true)
+            .build()
+            .unwrap();
+        for trailing_bytes in [TrailingPolicy::Drop, TrailingPolicy::PartialChunk, TrailingPolicy::Error] {
+            let mut reader = build(trailing_bytes);
+            let chunks = reader.chunks_complexf32().collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(chunks.len(), 3);
+            assert!(chunks.iter().all(|chunk| chunk.len() == 4));
+            let zero = Complex::new(0.0, 0.0);
+            assert_eq!(chunks[2], vec![Complex::new(8737.0, 9251.0), Complex::new(9765.0, 10279.0), zero, zero]);
+            assert_eq!(reader.padded_samples(), 2);
+        }
+        let mut reader = build(TrailingPolicy::Drop);
+        reader.read_next_chunk_complexf32().unwrap();
+        assert_eq!(reader.padded_samples(), 0);
+
+        let result = SdrFileReader::from_reader(Cursor::new(bytes), 4, SampleType::I16).pad_final_chunk(true).decimation_factor(2).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_from_reader_cursor() {
+        let mut bytes = Vec::new();
+        for value in [1i16, -2, 300, -400, 5000, -6000] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 2, SampleType::I16).build().unwrap();
+        assert_eq!(reader.total_samples().unwrap(), 3);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(1.0, -2.0), Complex::new(300.0, -400.0)]);
+        assert_eq!(reader.remaining_samples().unwrap(), 1);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_into_reuses_buffer() {
+        let build = || fixture_reader(1000)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut allocating_reader = build();
+        let mut reusing_reader = build();
+
+        let mut samples_f32 = Vec::new();
+        while let Some(n) = reusing_reader.read_next_chunk_into_f32(&mut samples_f32).unwrap() {
+            let expected = allocating_reader.read_next_chunk_complexf32().unwrap().unwrap();
+            assert_eq!(n, expected.len());
+            assert_eq!(samples_f32, expected);
+        }
+        assert!(samples_f32.is_empty());
+        assert_eq!(allocating_reader.read_next_chunk_complexf32().unwrap(), None);
+
+        let mut allocating_reader = build();
+        let mut reusing_reader = build();
+        let mut samples_f64 = Vec::new();
+        while let Some(n) = reusing_reader.read_next_chunk_into_f64(&mut samples_f64).unwrap() {
+            let expected = allocating_reader.read_next_chunk_complexf64().unwrap().unwrap();
+            assert_eq!(n, expected.len());
+            assert_eq!(samples_f64, expected);
+        }
+        assert_eq!(allocating_reader.read_next_chunk_complexf64().unwrap(), None);
+    }
+
+    #[test]
+    fn test_i24() {
+        // I = -2 (0xFFFFFE), Q = 0x123456
+        let bytes = vec![0xFE, 0xFF, 0xFF, 0x56, 0x34, 0x12];
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 1, SampleType::I24).build().unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(-2.0, 1_193_046.0)]);
+
+        // I = -8388608 (0x800000), Q = 1, big-endian
+        let bytes = vec![0x80, 0x00, 0x00, 0x00, 0x00, 0x01];
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 1, SampleType::I24)
+            .endianness(Endianness::Big)
+            .normalize(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.read_next_chunk_complexf64().unwrap().unwrap(), vec![Complex::new(-1.0, 1.0 / 8_388_608.0)]);
+    }
+
+    #[test]
+    fn test_metadata_from_gqrx_filename() {
+        let reader = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let metadata = reader.metadata().expect("Metadata should be parsed from the file name");
+        assert_eq!(metadata.center_freq_hz, 580_206_500);
+        assert_eq!(metadata.sample_rate_hz, 2_400_000);
+
+        let file_path = write_temp_file("no_metadata", &[0; 8]);
+        let reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.metadata(), None);
+    }
+
+    #[test]
+    fn test_interleaved_f32() {
+        let build = || fixture_reader(1024).build().expect("Failed to create SdrFileReader");
+        let mut complex_reader = build();
+        let mut interleaved_reader = build();
+
+        let mut interleaved = Vec::new();
+        while let Some(n) = interleaved_reader.read_next_chunk_interleaved_f32(&mut interleaved).unwrap() {
+            let samples = complex_reader.read_next_chunk_complexf32().unwrap().unwrap();
+            assert_eq!(n, samples.len());
+            assert_eq!(interleaved.len(), 2 * samples.len());
+            for (pair, sample) in interleaved.chunks_exact(2).zip(&samples) {
+                assert_eq!(Complex::new(pair[0], pair[1]), *sample);
+            }
+        }
+        assert_eq!(complex_reader.read_next_chunk_complexf32().unwrap(), None);
+
+        // The final chunk is padded like the complex chunks
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![1, 2, 3, 4, 5, 6]), 2, SampleType::I8).pad_final_chunk(true).build().unwrap();
+        assert_eq!(reader.read_next_chunk_interleaved_f32(&mut interleaved).unwrap(), Some(2));
+        assert_eq!(reader.read_next_chunk_interleaved_f32(&mut interleaved).unwrap(), Some(2));
+        assert_eq!(interleaved, vec![5.0, 6.0, 0.0, 0.0]);
+        assert_eq!(reader.read_next_chunk_interleaved_f32(&mut interleaved).unwrap(), None);
+    }
+
+    #[test]
+    fn test_generic_read_next_chunk() {
+        let build = || fixture_reader(1000).build().expect("Failed to create SdrFileReader");
+        let (mut generic, mut legacy) = (build(), build());
+        for _ in 0..3 {
+            assert_eq!(generic.read_next_chunk::<f32>().unwrap(), legacy.read_next_chunk_complexf32().unwrap());
+            assert_eq!(generic.read_next_chunk::<f64>().unwrap(), legacy.read_next_chunk_complexf64().unwrap());
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn test_frequency_shift_to_dc() {
+        // A tone at +3 kHz, with the initial phase at 1 radian
+        let (shift_hz, sample_rate_hz) = (3_000.0, 48_000);
+        let mut bytes = Vec::new();
+        for n in 0..1000 {
+            let sample = Complex::from_polar(0.5, 1.0 + std::f64::consts::TAU * shift_hz * f64::from(n) / sample_rate_hz as f64);
+            bytes.extend_from_slice(&(sample.re as f32).to_le_bytes());
+            bytes.extend_from_slice(&(sample.im as f32).to_le_bytes());
+        }
+        // 96 is not a multiple of the 16 sample period, so the phase has to carry over between chunks
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 96, SampleType::F32)
+            .frequency_shift_hz(shift_hz)
+            .sample_rate_hz(sample_rate_hz)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.chunks_complexf64().flat_map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(samples.len(), 1000);
+        for sample in samples {
+            assert!((sample - Complex::from_polar(0.5, 1.0)).norm() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_zero_samples_per_chunk() {
+        let result = SdrFileReader::from_reader(Cursor::new(vec![0u8; 16]), 0, SampleType::F32).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+        let result = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(0)
+            .sample_type(SampleType::F32)
+            .build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_open_error_contains_path() {
+        let file_path = "does_not_exist/capture.raw";
+        let result = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build();
+        let Err(error) = result else { panic!("Opened a nonexistent file") };
+        assert!(matches!(&error, SdrReaderError::Open { source, .. } if source.kind() == std::io::ErrorKind::NotFound));
+        assert!(error.to_string().contains(file_path), "{error}");
+    }
+
+    #[test]
+    fn test_frequency_shift_requires_sample_rate() {
+        let result = SdrFileReader::from_reader(Cursor::new(Vec::new()), 4, SampleType::F32)
+            .frequency_shift_hz(1_000.0)
+            .build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_swap_iq() {
+        let mut bytes = Vec::new();
+        for value in [1i16, -2, 300, -400] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let build = |swap_iq| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 2, SampleType::I16)
+            .swap_iq(swap_iq)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let default = build(false).read_next_chunk_complexf32().unwrap().unwrap();
+        let swapped = build(true).read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(default, vec![Complex::new(1.0, -2.0), Complex::new(300.0, -400.0)]);
+        assert_eq!(swapped, default.iter().map(|sample| Complex::new(sample.im, sample.re)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_conjugate() {
+        let mut bytes = Vec::new();
+        for value in [1i16, -2, 300, -400] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let build = |conjugate| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 2, SampleType::I16)
+            .conjugate(conjugate)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let default = build(false).read_next_chunk_complexf64().unwrap().unwrap();
+        let conjugated = build(true).read_next_chunk_complexf64().unwrap().unwrap();
+        assert_eq!(conjugated, default.iter().map(Complex::conj).collect::<Vec<_>>());
+        assert_eq!(conjugated, vec![Complex::new(1.0, 2.0), Complex::new(300.0, 400.0)]);
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_remove_dc() {
+        // A tone with a constant offset of 0.25 - 0.5j
+        let mut bytes = Vec::new();
+        for n in 0..20_000 {
+            let sample = Complex::from_polar(0.5, std::f64::consts::TAU * f64::from(n) / 16.0) + Complex::new(0.25, -0.5);
+            bytes.extend_from_slice(&(sample.re as f32).to_le_bytes());
+            bytes.extend_from_slice(&(sample.im as f32).to_le_bytes());
+        }
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 1000, SampleType::F32)
+            .remove_dc(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mean = |chunk: &[Complex<f64>]| chunk.iter().sum::<Complex<f64>>() / 1000.0;
+        let first_chunk = reader.read_next_chunk_complexf64().unwrap().unwrap();
+        let last_chunk = reader.chunks_complexf64().last().unwrap().unwrap();
+        assert!(mean(&last_chunk).norm() < 1e-2);
+        assert!(mean(&last_chunk).norm() < mean(&first_chunk).norm());
+        assert!((reader.dc_offset() - Complex::new(0.25, -0.5)).norm() < 1e-2);
+    }
+
+    #[test]
+    fn test_gzip() {
+        use std::io::Write;
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&std::fs::read(file_path).unwrap()).unwrap();
+        let gzip_path = std::env::temp_dir().join("gqrx_20240929_015218_580206500_2400000_fc.raw.gz");
+        std::fs::write(&gzip_path, encoder.finish().unwrap()).unwrap();
+
+        let build = |path: &Path| SdrFileReader::builder()
+            .file_path(path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let expected = build(Path::new(file_path)).chunks_complexf32().map(Result::unwrap).collect::<Vec<_>>();
+        // Detected from the .gz extension
+        let mut reader = build(&gzip_path);
+        assert!(reader.metadata().is_some());
+        assert!(matches!(reader.total_samples(), Err(SdrReaderError::Io(why)) if why.kind() == ErrorKind::Unsupported));
+        assert_eq!(reader.chunks_complexf32().map(Result::unwrap).collect::<Vec<_>>(), expected);
+
+        // Forcing it off reads the compressed bytes as samples
+        let mut reader = SdrFileReader::builder()
+            .file_path(&gzip_path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .gzip(false)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_ne!(reader.read_next_chunk_complexf32().unwrap().as_ref(), expected.first());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let compressed = zstd::encode_all(std::fs::File::open(file_path).unwrap(), 3).unwrap();
+        let zstd_path = std::env::temp_dir().join("gqrx_20240929_015218_580206500_2400000_fc.raw.zst");
+        std::fs::write(&zstd_path, compressed).unwrap();
+
+        let build = |path: &Path| SdrFileReader::builder()
+            .file_path(path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let expected = build(Path::new(file_path)).read_all_complexf32().unwrap();
+        // Detected from the .zst extension
+        let mut reader = build(&zstd_path);
+        assert!(reader.metadata().is_some());
+        assert!(matches!(reader.total_samples(), Err(SdrReaderError::Io(why)) if why.kind() == ErrorKind::Unsupported));
+        assert_eq!(reader.read_all_complexf32().unwrap(), expected);
+
+        let result = SdrFileReader::builder()
+            .file_path(&zstd_path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .gzip(true)
+            .build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_power_and_magnitude() {
+        let build = || fixture_reader(1000).build().expect("Failed to create SdrFileReader");
+        let (mut complex_reader, mut power_reader, mut magnitude_reader) = (build(), build(), build());
+        while let Some(samples) = complex_reader.read_next_chunk_complexf32().unwrap() {
+            let power = power_reader.read_next_chunk_power_f32().unwrap().unwrap();
+            let magnitude = magnitude_reader.read_next_chunk_magnitude_f32().unwrap().unwrap();
+            assert_eq!(power.len(), samples.len());
+            for (power, sample) in power.iter().zip(&samples) {
+                assert!((power - sample.norm().powi(2)).abs() < 1e-6);
+            }
+            assert_eq!(magnitude, samples.iter().map(|sample| sample.norm()).collect::<Vec<_>>());
+        }
+        assert_eq!(power_reader.read_next_chunk_power_f32().unwrap(), None);
+        assert_eq!(magnitude_reader.read_next_chunk_magnitude_f32().unwrap(), None);
+    }
+
+    #[test]
+    fn test_sample_rate_and_center_freq() {
+        let reader = SdrFileReader::from_reader(Cursor::new(Vec::new()), 4, SampleType::I16)
+            .sample_rate_hz(2_048_000)
+            .center_freq_hz(100_000_000)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.sample_rate_hz(), Some(2_048_000));
+        assert_eq!(reader.center_freq_hz(), Some(100_000_000));
+
+        let reader = SdrFileReader::from_reader(Cursor::new(Vec::new()), 4, SampleType::I16)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.sample_rate_hz(), None);
+        assert_eq!(reader.center_freq_hz(), None);
+
+        // Taken from the gqrx file name, unless overridden
+        let reader = SdrFileReader::from_gqrx_filename("gqrx_20240929_015218_580206500_2400000_fc.raw", 1024)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.sample_rate_hz(), Some(2_400_000));
+        assert_eq!(reader.center_freq_hz(), Some(580_206_500));
+    }
+
+    #[test]
+    fn test_sample_duration_conversion() {
+        let reader = SdrFileReader::from_reader(Cursor::new(Vec::new()), 4, SampleType::I16)
+            .sample_rate_hz(2_400_000)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.sample_to_duration(0), Some(Duration::ZERO));
+        assert_eq!(reader.sample_to_duration(8_400_000), Some(Duration::from_millis(3_500)));
+        assert_eq!(reader.sample_to_duration(1), Some(Duration::from_nanos(416)));
+        assert_eq!(reader.duration_to_sample(Duration::from_millis(3_500)), Some(8_400_000));
+        assert_eq!(reader.duration_to_sample(Duration::from_nanos(417)), Some(1));
+
+        let reader = SdrFileReader::from_reader(Cursor::new(Vec::new()), 4, SampleType::I16)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.sample_to_duration(100), None);
+        assert_eq!(reader.duration_to_sample(Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn test_read_all() {
+        // 10 I16 samples plus one stray byte
+        let file_path = write_temp_file("read_all", &(0..41).collect::<Vec<u8>>());
+        let build = |trailing_bytes| SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(4)
+            .sample_type(SampleType::I16)
+            .trailing_bytes(trailing_bytes)
+            .build()
+            .expect("Failed to create SdrFileReader");
+
+        let expected = build(TrailingPolicy::Drop).chunks_complexf32().map(Result::unwrap).collect::<Vec<_>>().concat();
+        let all_samples = build(TrailingPolicy::Drop).read_all_complexf32().unwrap();
+        assert_eq!(all_samples.len(), 8);
+        assert_eq!(all_samples, expected);
+
+        let expected = build(TrailingPolicy::Drop).chunks_complexf64().map(Result::unwrap).collect::<Vec<_>>().concat();
+        assert_eq!(build(TrailingPolicy::Drop).read_all_complexf64().unwrap(), expected);
+
+        // The stray byte is reported after the last whole samples
+        assert!(matches!(build(TrailingPolicy::PartialChunk).read_all_complexf32(), Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 1 })));
+
+        // Starting after the first chunk
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        reader.read_next_chunk_complexf32().unwrap();
+        assert_eq!(reader.read_all_complexf32().unwrap().len(), 16 * 1024 - 1000);
+    }
+
+    #[test]
+    fn test_peek_next_chunk() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .frequency_shift_hz(1_000.0)
+            .decimation_factor(3)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        reader.read_next_chunk_complexf32().unwrap();
+        for _ in 0..3 {
+            let peeked = reader.peek_next_chunk_complexf32().unwrap();
+            assert!(peeked.is_some());
+            assert_eq!(reader.peek_next_chunk_complexf32().unwrap(), peeked);
+            assert_eq!(reader.read_next_chunk_complexf32().unwrap(), peeked);
+        }
+
+        // Peeking the padded final chunk doesn't report its padding before it is read
+        let bytes = (1..=40).collect::<Vec<u8>>();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 4, SampleType::I16).pad_final_chunk(true).build().unwrap();
+        reader.read_next_chunk_complexf32().unwrap();
+        reader.read_next_chunk_complexf32().unwrap();
+        let peeked = reader.peek_next_chunk_complexf32().unwrap();
+        assert_eq!(peeked.as_ref().map(Vec::len), Some(4));
+        assert_eq!(reader.padded_samples(), 0);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), peeked);
+        assert_eq!(reader.padded_samples(), 2);
+
+        // Gzip-compressed files can't be seeked
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &[0u8; 64]).unwrap();
+        let gzip_path = std::env::temp_dir().join("sdr_iq_file_reader_peek.raw.gz");
+        std::fs::write(&gzip_path, encoder.finish().unwrap()).unwrap();
+        let mut reader = SdrFileReader::builder()
+            .file_path(&gzip_path)
+            .samples_per_chunk(4)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert!(matches!(reader.peek_next_chunk_complexf32(), Err(SdrReaderError::Io(why)) if why.kind() == ErrorKind::Unsupported));
+    }
+
+    #[test]
+    fn test_buffer_capacity() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let build = |buffer_capacity| SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .maybe_buffer_capacity(buffer_capacity)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let expected = build(None).read_all_complexf32().unwrap();
+        // Larger than the whole file, and smaller than a single sample
+        for capacity in [1 << 20, 3] {
+            let mut reader = build(Some(capacity));
+            assert_eq!(reader.read_all_complexf32().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_samples_iterator() {
+        let build = || fixture_reader(1024).build().expect("Failed to create SdrFileReader");
+        let mut chunked = build();
+        let mut expected = chunked.read_next_chunk_complexf32().unwrap().unwrap();
+        expected.extend(chunked.read_next_chunk_complexf32().unwrap().unwrap());
+
+        let mut reader = build();
+        let samples = reader.samples_complexf32().take(2048).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(samples, expected);
+        assert_eq!(reader.samples_complexf64().count(), 14 * 1024);
+    }
+
+    #[test]
+    fn test_read_chunk_at() {
+        let build = || fixture_reader(1000)
+            .frequency_shift_hz(1234.5)
+            .sample_rate_hz(48_000)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let even_chunks = build().chunks_complexf32().step_by(2).take(3).collect::<Result<Vec<_>, _>>().unwrap();
+
+        let mut reader = build();
+        for (index, expected) in [4, 0, 2].into_iter().zip([&even_chunks[2], &even_chunks[0], &even_chunks[1]]) {
+            let chunk = reader.read_chunk_at(index).unwrap().unwrap();
+            let max_error = chunk.iter().zip(expected).map(|(a, b)| (a - b).norm()).fold(0.0, f32::max);
+            assert!(max_error < 1e-3);
+        }
+        assert!(reader.read_chunk_at(1000).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_seek_to_time() {
+        // 5 seconds at 1 kHz, each sample holds its index
+        let bytes = (0..5000i16).flat_map(|n| [n.to_le_bytes(), (-n).to_le_bytes()].concat()).collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_bytes(bytes.clone(), 100, SampleType::I16).sample_rate_hz(1000).build().unwrap();
+        reader.seek_to_time(Duration::from_millis(3500)).unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap()[0], Complex::new(3500.0, -3500.0));
+        reader.seek_to_time(Duration::from_secs(5)).unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+        assert!(matches!(reader.seek_to_time(Duration::from_millis(5001)), Err(SdrReaderError::OutOfRange(_))));
+
+        let mut reader = SdrFileReader::from_bytes(bytes, 100, SampleType::I16).build().unwrap();
+        assert!(matches!(reader.seek_to_time(Duration::from_secs(1)), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_into_inner() {
+        // Two chunks of samples followed by a trailer
+        let mut bytes = (1..=8i16).flat_map(i16::to_le_bytes).collect::<Vec<_>>();
+        bytes.extend_from_slice(b"TRAILER");
+        let mut reader = SdrFileReader::from_bytes(bytes, 2, SampleType::I16).build().unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(1.0, 2.0), Complex::new(3.0, 4.0)]);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(5.0, 6.0), Complex::new(7.0, 8.0)]);
+        let mut trailer = String::new();
+        reader.into_inner().read_to_string(&mut trailer).unwrap();
+        assert_eq!(trailer, "TRAILER");
+    }
+
+    #[test]
+    fn test_validate() {
+        // 100 I24 samples, one byte short
+        let build = |len| SdrFileReader::from_reader(Cursor::new(vec![0u8; len]), 10, SampleType::I24).build().unwrap();
+        assert!(build(600).validate().is_ok());
+        assert!(matches!(build(599).validate(), Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 5 })));
+    }
+
+    #[test]
+    fn test_sample_type_derives() {
+        let sample_type = SampleType::I16;
+        let copy = sample_type;
+        #[allow(clippy::clone_on_copy)]
+        let clone = sample_type.clone();
+        assert_eq!(format!("{copy:?}"), "I16");
+        assert_eq!(clone, SampleType::I16);
+        assert_ne!(clone, SampleType::U16);
+        let unique = [SampleType::F32, SampleType::F32, SampleType::U8].into_iter().collect::<std::collections::HashSet<_>>();
+        assert_eq!(unique.len(), 2);
+    }
+
+    #[test]
+    fn test_sample_type_from_str() {
+        // Each alias parses to the sample type that displays as the first name
+        let aliases = [
+            "u8 cu8 uint8 CU8",
+            "i8 ci8 s8 cs8 int8",
+            "u16 cu16 uint16 cu16_le",
+            "i16 ci16 s16 cs16 int16 ci16_le ci16_be CS16",
+            "i24 ci24 s24 cs24 int24",
+            "f32 cf32 fc32 float32 complex64 cf32_le CF32",
+            "f64 cf64 fc64 float64 complex128 cf64_be",
+        ];
+        for names in aliases {
+            let display = names.split(' ').next().unwrap();
+            for name in names.split(' ') {
+                assert_eq!(name.parse::<SampleType>().unwrap().to_string(), display, "{name}");
+            }
+        }
+        assert!(matches!("c32".parse::<SampleType>(), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!("cf32_xx".parse::<SampleType>(), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_sample_type_byte_code() {
+        let sample_types = [
+            SampleType::U8,
+            SampleType::I8,
+            SampleType::U16,
+            SampleType::I16,
+            SampleType::I24,
+            SampleType::F16,
+            SampleType::F32,
+            SampleType::F64,
+        ];
+        for sample_type in sample_types {
+            assert_eq!(SampleType::try_from(sample_type.as_u8()).unwrap(), sample_type);
+        }
+        assert_eq!(SampleType::F32.as_u8(), 6);
+        assert!(matches!(SampleType::try_from(8), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!(SampleType::try_from(255), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_read_samples() {
+        let build = || fixture_reader(1024).trailing_bytes(TrailingPolicy::PartialChunk).build().unwrap();
+        let all = build().read_all_complexf32().unwrap();
+        let mut reader = build();
+        assert_eq!(reader.read_samples_complexf32(3000).unwrap(), all[..3000]);
+        // Chunk reads continue after the samples, with the configured chunk size
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), all[3000..4024]);
+        assert_eq!(reader.read_samples_complexf32(3000).unwrap(), all[4024..7024]);
+        // 16384 samples in total
+        assert_eq!(reader.read_samples_complexf32(20_000).unwrap(), all[7024..]);
+        assert!(reader.read_samples_complexf32(3000).unwrap().is_empty());
+
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0; 80]), 4, SampleType::U8).overlap(2).build().unwrap();
+        assert!(matches!(reader.read_samples_complexf32(10), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_read_samples_decimated() {
+        let build = || fixture_reader(1024)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .decimation_factor(4)
+            .build()
+            .unwrap();
+        let all = build().read_all_complexf32().unwrap();
+        let mut reader = build();
+        // `n` counts the samples before decimation
+        assert_eq!(reader.read_samples_complexf32(1000).unwrap(), all[..250]);
+        // Source samples 1000 to 1997 start in step with the decimation, 1996 is the last one kept
+        assert_eq!(reader.read_samples_complexf32(998).unwrap(), all[250..500]);
+        assert!(reader.read_samples_complexf32(2).unwrap().is_empty());
+        assert_eq!(reader.read_samples_complexf32(4).unwrap(), all[500..501]);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), all[501..757]);
+    }
+
+    #[test]
+    fn test_next_raw_chunk() {
+        let bytes = (0..1000i16).flat_map(|n| (n * 31 - 15_000).to_le_bytes()).collect::<Vec<_>>();
+        let mut raw_reader = SdrFileReader::from_reader(Cursor::new(bytes.clone()), 64, SampleType::I16)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .unwrap();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 64, SampleType::I16)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .unwrap();
+        let mut chunks = 0;
+        while let Some(raw) = raw_reader.next_raw_chunk().unwrap() {
+            let decoded = raw
+                .chunks_exact(4)
+                .map(|sample| {
+                    let i = i16::from_le_bytes([sample[0], sample[1]]);
+                    let q = i16::from_le_bytes([sample[2], sample[3]]);
+                    Complex::new(f32::from(i), f32::from(q))
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), decoded);
+            chunks += 1;
+        }
+        // 500 samples make 7 full chunks of 64 and a partial one of 52
+        assert_eq!(chunks, 8);
+        assert!(reader.read_next_chunk_complexf32().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_position() {
+        let mut reader = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.sample_position().unwrap(), 0);
+        reader.read_next_chunk_complexf32().unwrap();
+        // The BufReader has read ahead, but the position only counts the samples handed out
+        assert_eq!(reader.sample_position().unwrap(), 1000);
+        assert_eq!(reader.byte_position().unwrap(), 8000);
+        reader.seek_to_sample(5000).unwrap();
+        assert_eq!(reader.sample_position().unwrap(), 5000);
+    }
+
+    #[test]
+    fn test_overlap() {
+        let build = |frequency_shift_hz| SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .overlap(512)
+            .maybe_frequency_shift_hz(frequency_shift_hz)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut reader = build(None);
+        let first = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        let second = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(second[..512], first[512..]);
+        assert_eq!(reader.sample_position().unwrap(), 1024);
+        // 16 chunks without overlap, so 31 chunks that start every 512 samples
+        assert_eq!(reader.chunks_complexf32().count(), 31 - 2);
+
+        let mut reader = build(Some(100_000.0));
+        let first = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        let second = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        for (a, b) in second[..512].iter().zip(&first[512..]) {
+            assert!((a - b).norm() < 1e-3);
+        }
+
+        let result = SdrFileReader::from_reader(Cursor::new(Vec::new()), 16, SampleType::F32).overlap(16).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_real_component() {
+        let build = || fixture_reader(1024).build().expect("Failed to create SdrFileReader");
+        let chunk = build().read_next_chunk_complexf32().unwrap().unwrap();
+        let i = build().read_next_chunk_real_f32(Component::I).unwrap().unwrap();
+        let q = build().read_next_chunk_real_f32(Component::Q).unwrap().unwrap();
+        let (re, im): (Vec<_>, Vec<_>) = chunk.iter().map(|sample| (sample.re, sample.im)).unzip();
+        assert_eq!(i, re);
+        assert_eq!(q, im);
+    }
+
+    #[test]
+    fn test_config_accessors() {
+        let reader = SdrFileReader::from_reader(Cursor::new(Vec::new()), 1000, SampleType::I24).build().unwrap();
+        assert_eq!(reader.sample_type(), SampleType::I24);
+        assert_eq!(reader.samples_per_chunk(), 1000);
+        assert_eq!(reader.bytes_per_chunk(), 6000);
+    }
+
+    #[test]
+    fn test_header_skip_bytes() {
+        let mut bytes = vec![0xAA; 44];
+        for value in [1i16, -2, 3, -4] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 2, SampleType::I16)
+            .header_skip_bytes(44)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.total_samples().unwrap(), 2);
+        assert!(reader.validate().is_ok());
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(1.0, -2.0), Complex::new(3.0, -4.0)]);
+        assert_eq!(reader.remaining_samples().unwrap(), 0);
+        reader.seek_to_sample(1).unwrap();
+        assert_eq!(reader.sample_position().unwrap(), 1);
+        assert_eq!(reader.byte_position().unwrap(), 48);
+    }
+
+    #[test]
+    fn test_detect_endianness() {
+        let mut reader = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let first_chunk = reader.peek_next_chunk_complexf32().unwrap();
+        assert!(matches!(reader.detect_endianness().unwrap(), Endianness::Little));
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), first_chunk);
+
+        let mut bytes = Vec::new();
+        for n in 0..100u8 {
+            bytes.extend_from_slice(&(f64::from(n) / 100.0 - 0.5).to_be_bytes());
+        }
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 10, SampleType::F64).build().unwrap();
+        assert!(matches!(reader.detect_endianness().unwrap(), Endianness::Big));
+
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0u8; 8]), 1, SampleType::I16).build().unwrap();
+        assert!(matches!(reader.detect_endianness(), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_advance_samples() {
+        let bytes = (0..400i16).flat_map(i16::to_le_bytes).collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 10, SampleType::I16).build().unwrap();
+        assert_eq!(reader.advance_samples(30).unwrap(), 30);
+        assert_eq!(reader.byte_position().unwrap(), 120);
+        let chunk = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(chunk[0], Complex::new(60.0, 61.0));
+        // 200 samples in total, 40 have been consumed
+        assert_eq!(reader.advance_samples(1000).unwrap(), 160);
+        assert_eq!(reader.byte_position().unwrap(), 800);
+        assert_eq!(reader.advance_samples(1).unwrap(), 0);
+        assert!(reader.read_next_chunk_complexf32().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_skip_samples() {
+        const FILE_PATH: &str = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let all_samples = SdrFileReader::from_reader(std::fs::File::open(FILE_PATH).unwrap(), 1000, SampleType::F32)
+            .build()
+            .unwrap()
+            .read_all_complexf32()
+            .unwrap();
+
+        let mut seekable = SdrFileReader::builder()
+            .file_path(FILE_PATH)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        seekable.skip_samples(1500).unwrap();
+        assert_eq!(seekable.sample_position().unwrap(), 1500);
+        assert_eq!(seekable.read_next_chunk_complexf32().unwrap().unwrap(), all_samples[1500..2500]);
+
+        // Chaining hides the `Seek` implementation of the cursor, like a pipe
+        let bytes = std::fs::read(FILE_PATH).unwrap();
+        let mut pipe = SdrFileReader::from_reader(Cursor::new(bytes).chain(std::io::empty()), 1000, SampleType::F32)
+            .overlap(500)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        pipe.skip_samples(250).unwrap();
+        assert_eq!(pipe.read_next_chunk_complexf32().unwrap().unwrap(), all_samples[250..1250]);
+        // 200 of the 500 overlapping samples are skipped
+        pipe.skip_samples(200).unwrap();
+        assert_eq!(pipe.read_next_chunk_complexf32().unwrap().unwrap(), all_samples[950..1950]);
+        pipe.skip_samples(u64::MAX).unwrap();
+        assert!(pipe.read_next_chunk_complexf32().unwrap().is_none());
+    }
+
+    /// A seekable reader that counts the bytes read from it.
+    struct CountingReader {
+        inner: Cursor<Vec<u8>>,
+        bytes_read: usize,
+    }
+
+    impl Read for CountingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.inner.read(buf)?;
+            self.bytes_read += len;
+            Ok(len)
+        }
+    }
+
+    impl Seek for CountingReader {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_skip_samples_seeks() {
+        #[allow(clippy::cast_precision_loss)]
+        let samples = (0..100_000).map(|n| Complex::new(n as f32, -n as f32)).collect::<Vec<_>>();
+        let bytes = samples.iter().flat_map(|sample| [sample.re.to_le_bytes(), sample.im.to_le_bytes()]).flatten().collect::<Vec<_>>();
+        let counting = |hashing| {
+            SdrFileReader::from_reader(CountingReader { inner: Cursor::new(bytes.clone()), bytes_read: 0 }, 100, SampleType::F32)
+                .hashing(hashing)
+                .buffer_capacity(1024)
+                .build()
+                .unwrap()
+                .with_seek()
+        };
+
+        let mut reader = counting(false);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), samples[..100]);
+        assert_eq!(reader.advance_samples(50_000).unwrap(), 50_000);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), samples[50_100..50_200]);
+        // Only the two chunks and the buffered bytes after them were read, not the 400 KB in between
+        assert!(reader.reader.get_ref().bytes_read < 4096);
+        assert_eq!(reader.advance_samples(u64::MAX).unwrap(), 100_000 - 50_200);
+        assert!(reader.read_next_chunk_complexf32().unwrap().is_none());
+
+        // The digest needs the skipped bytes
+        let mut reader = counting(true);
+        reader.skip_samples(50_000).unwrap();
+        assert!(reader.reader.get_ref().bytes_read >= 400_000);
+    }
+
+    #[test]
+    fn test_bytes_remaining() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        reader.read_next_chunk_complexf32().unwrap();
+        let file_len = std::fs::metadata(file_path).unwrap().len();
+        assert_eq!(reader.bytes_remaining().unwrap(), file_len - 8000);
+        assert_eq!(reader.remaining_samples().unwrap(), reader.bytes_remaining().unwrap() / 8);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut reader = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .frequency_shift_hz(12_345.0)
+            .remove_dc(true)
+            .decimation_factor(3)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let first_pass = reader.read_all_complexf32().unwrap();
+        assert!(reader.read_next_chunk_complexf32().unwrap().is_none());
+        reader.reset().unwrap();
+        assert_eq!(reader.dc_offset(), Complex::new(0.0, 0.0));
+        let second_pass = reader.read_all_complexf32().unwrap();
+        assert_eq!(first_pass, second_pass);
+    }
+
+    #[test]
+    fn test_normalize_with() {
+        // 12-bit samples, right-justified in 16-bit words
+        let bytes = [2047i16, -2048].iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        let build = |full_scale| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 1, SampleType::I16)
+            .normalize_with(full_scale)
+            .build();
+        let samples = build(2048.0).unwrap().read_next_chunk_complexf64().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(2047.0 / 2048.0, -1.0)]);
+        assert!(matches!(build(0.0), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!(build(f64::NAN), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_bit_shift() {
+        // 12-bit samples, left-justified in 16-bit words with noise in the low 4 bits
+        let bytes = [(2047i16 << 4) | 0xF, -2048 << 4].iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        let build = |bit_shift| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 1, SampleType::I16)
+            .bit_shift(bit_shift)
+            .build();
+        let samples = build(4).unwrap().read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(2047.0, -2048.0)]);
+        let samples = build(0).unwrap().read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(32767.0, -32768.0)]);
+        assert!(matches!(build(16), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_offset_binary() {
+        // -1000 and 1000 as offset binary are 32768 - 1000 and 32768 + 1000
+        let bytes = [31_768u16, 33_768].iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        let build = |offset_binary| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 1, SampleType::I16)
+            .offset_binary(offset_binary)
+            .build()
+            .unwrap();
+        let samples = build(true).read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(-1000.0, 1000.0)]);
+        let samples = build(false).read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(31_768.0, -31_768.0)]);
+
+        // The extremes of I8 and I24, and I24 with a shift
+        let mut reader = SdrFileReader::from_bytes(vec![0x00, 0xFF], 1, SampleType::I8).build().unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(0.0, -1.0)]);
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0x00, 0xFF]), 1, SampleType::I8).offset_binary(true).build().unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(-128.0, 127.0)]);
+        let bytes = vec![0x00, 0x00, 0x00, 0xF0, 0xFF, 0xFF];
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 1, SampleType::I24).offset_binary(true).bit_shift(4).build().unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), vec![Complex::new(-524_288.0, 524_287.0)]);
+    }
+
+    #[test]
+    fn test_split() {
+        let build = |gain: Option<f32>| SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .maybe_gain(gain)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        // Decoded directly, and through the processing
+        for gain in [None, Some(2.0)] {
+            let (mut complex, mut split) = (build(gain), build(gain));
+            for _ in 0..3 {
+                let samples = complex.read_next_chunk_complexf32().unwrap().unwrap();
+                let (i, q) = split.read_next_chunk_split_f32().unwrap().unwrap();
+                assert_eq!(i, samples.iter().map(|sample| sample.re).collect::<Vec<_>>());
+                assert_eq!(q, samples.iter().map(|sample| sample.im).collect::<Vec<_>>());
+            }
+        }
+
+        let bytes = vec![1, 2, 3, 4, 5, 6];
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 2, SampleType::I8).pad_final_chunk(true).build().unwrap();
+        assert_eq!(reader.read_next_chunk_split_f32().unwrap(), Some((vec![1.0, 3.0], vec![2.0, 4.0])));
+        assert_eq!(reader.read_next_chunk_split_f32().unwrap(), Some((vec![5.0, 0.0], vec![6.0, 0.0])));
+        assert_eq!(reader.padded_samples(), 1);
+        assert_eq!(reader.read_next_chunk_split_f32().unwrap(), None);
+    }
+
+    #[test]
+    fn test_smoothed_power() {
+        // A step from silence to a power of 1.0 near the end of the second chunk
+        let bytes = (0..40u8).flat_map(|n| if n < 18 { [0, 0] } else { [1, 0] }).collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_bytes(bytes, 10, SampleType::I8).build().unwrap();
+        let mut power = Vec::new();
+        while let Some(chunk) = reader.read_next_chunk_smoothed_power_f32(4).unwrap() {
+            power.extend(chunk);
+        }
+        assert_eq!(power.len(), 40);
+        assert!(power[..18].iter().all(|&value| value == 0.0));
+        // The step ramps up over the window of 4 samples, across the chunk boundary
+        let ramp = [0.25, 0.5, 0.75, 1.0, 1.0];
+        for (value, expected) in power[18..23].iter().zip(ramp) {
+            assert!((value - expected).abs() < 1e-6, "{value} instead of {expected}");
+        }
+        assert!(power[21..].iter().all(|&value| (value - 1.0).abs() < 1e-6));
+
+        let mut reader = SdrFileReader::from_bytes(vec![0; 16], 8, SampleType::I8).build().unwrap();
+        assert!(matches!(reader.read_next_chunk_smoothed_power_f32(0), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_resample() {
+        // A tone at 100 kHz, sampled at 2.4 MHz
+        let bytes = (0..48_000u32)
+            .map(|n| Complex::from_polar(0.5, std::f64::consts::TAU * 100e3 * f64::from(n) / 2.4e6))
+            .flat_map(|sample: Complex<f64>| [sample.re.to_le_bytes(), sample.im.to_le_bytes()])
+            .flatten()
+            .collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 1000, SampleType::F64)
+            .resample((2_400_000, 2_048_000))
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let chunk_lens = reader.chunks_complexf64().map(|chunk| chunk.unwrap().len()).collect::<Vec<_>>();
+        assert!(chunk_lens.contains(&853) && chunk_lens.contains(&854));
+        assert_eq!(chunk_lens.iter().sum::<usize>(), 40_960);
+
+        reader.reset().unwrap();
+        let samples = reader.read_all_complexf64().unwrap();
+        // After the filter delay, each sample is rotated by the phase step of 100 kHz at 2.048 MHz
+        let expected_step = std::f64::consts::TAU * 100e3 / 2.048e6;
+        for pair in samples[100..].windows(2) {
+            assert!(((pair[1] / pair[0]).arg() - expected_step).abs() < 1e-3);
+            assert!((pair[1].norm() - 0.5).abs() < 1e-3);
+        }
+
+        let build = |resample, overlap| SdrFileReader::from_reader(Cursor::new(Vec::new()), 10, SampleType::F32)
+            .resample(resample)
+            .overlap(overlap)
+            .build();
+        assert!(matches!(build((0, 1), 0), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!(build((2, 1), 5), Err(SdrReaderError::InvalidConfig(_))));
+        // Nearly coprime rates would need millions of filter taps
+        assert!(matches!(build((2_400_000, 2_047_999), 0), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!(build((u64::MAX, 1), 0), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_for_each_sample() {
+        let build = || fixture_reader(1000).build().expect("Failed to create SdrFileReader");
+        let (mut sum, mut count) = (0.0f32, 0);
+        build().for_each_sample_f32(|sample| {
+            sum += sample.norm();
+            count += 1;
+        }).unwrap();
+        let expected = build().chunks_complexf32().flat_map(Result::unwrap).map(Complex::norm).sum::<f32>();
+        assert_eq!(count, 16 * 1000);
+        assert!((sum - expected).abs() <= expected.abs() * 1e-6);
+    }
+
+    #[test]
+    fn test_channels() {
+        // Channel 0 counts up, channel 1 counts down
+        let bytes = (0..8i16)
+            .flat_map(|n| [n, -n, 100 - n, n - 100])
+            .flat_map(i16::to_le_bytes)
+            .collect::<Vec<_>>();
+        let build = || SdrFileReader::from_reader(Cursor::new(bytes.clone()), 8, SampleType::I16)
+            .channels(2)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut reader = build();
+        let first = reader.read_next_chunk_channel_f32(0).unwrap().unwrap();
+        assert_eq!(first, (0..4i16).map(|n| Complex::new(f32::from(n), -f32::from(n))).collect::<Vec<_>>());
+        let second = reader.read_next_chunk_channel_f32(1).unwrap().unwrap();
+        assert_eq!(second, (4..8i16).map(|n| Complex::new(f32::from(100 - n), f32::from(n - 100))).collect::<Vec<_>>());
+        assert!(reader.read_next_chunk_channel_f32(1).unwrap().is_none());
+        assert!(matches!(build().read_next_chunk_channel_f32(2), Err(SdrReaderError::InvalidConfig(_))));
+
+        let build = |samples_per_chunk, remove_dc| SdrFileReader::from_reader(Cursor::new(Vec::new()), samples_per_chunk, SampleType::I16)
+            .channels(4)
+            .remove_dc(remove_dc)
+            .build();
+        assert!(build(8, false).is_ok());
+        assert!(matches!(build(6, false), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!(build(8, true), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_digest() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let bytes = std::fs::read(file_path).unwrap();
+        let digest = |path: &Path| {
+            let mut reader = SdrFileReader::builder()
+                .file_path(path)
+                .samples_per_chunk(1000)
+                .sample_type(SampleType::F32)
+                .hashing(true)
+                .build()
+                .expect("Failed to create SdrFileReader");
+            reader.peek_next_chunk_complexf32().unwrap();
+            reader.read_all_complexf32().unwrap();
+            reader.digest().unwrap()
+        };
+        let first = digest(Path::new(file_path));
+        assert_eq!(digest(Path::new(file_path)), first);
+        // The last 384 samples don't fill a chunk and are dropped, so only the first 16000 samples are hashed
+        assert_eq!(first, crc32fast::hash(&bytes[..16_000 * 8]));
+
+        let mut modified = bytes.clone();
+        modified[100] ^= 1;
+        assert_ne!(digest(&write_temp_file("digest_modified", &modified)), first);
+
+        let reader = SdrFileReader::from_reader(Cursor::new(bytes), 1000, SampleType::F32).build().unwrap();
+        assert!(reader.digest().is_none());
+    }
+
+    /// A reader that fails with `ErrorKind::Interrupted` before every read that succeeds, like a pipe receiving signals.
+    struct InterruptedReader {
+        inner: Cursor<Vec<u8>>,
+        interrupt: bool,
+    }
+
+    impl Read for InterruptedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(std::io::Error::from(ErrorKind::Interrupted));
+            }
+            // Hand out at most 3 bytes at a time, so chunks take several reads
+            let len = buf.len().min(3);
+            self.inner.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn test_interrupted_reads_are_retried() {
+        for trailing_bytes in [TrailingPolicy::Drop, TrailingPolicy::PartialChunk] {
+            let reader = InterruptedReader { inner: Cursor::new((0..40).collect()), interrupt: false };
+            let mut reader = SdrFileReader::from_reader(reader, 8, SampleType::I16)
+                .trailing_bytes(trailing_bytes)
+                .buffer_capacity(1)
+                .build()
+                .expect("Failed to create SdrFileReader");
+            let chunks = reader.chunks_complexf32().collect::<Result<Vec<_>, _>>().unwrap();
+            let chunk_lens = chunks.iter().map(Vec::len).collect::<Vec<_>>();
+            assert_eq!(chunk_lens, if trailing_bytes == TrailingPolicy::PartialChunk { vec![8, 2] } else { vec![8] });
+            assert_eq!(chunks[0][0], Complex::new(f32::from(i16::from_le_bytes([0, 1])), f32::from(i16::from_le_bytes([2, 3]))));
+        }
+    }
+
+    #[test]
+    fn test_phase() {
+        // A phasor that rotates by 0.1 radians per sample
+        let bytes = (0..100u8)
+            .map(|n| Complex::from_polar(0.5f32, f32::from(n) * 0.1))
+            .flat_map(|sample| [sample.re.to_le_bytes(), sample.im.to_le_bytes()])
+            .flatten()
+            .collect::<Vec<_>>();
+        let build = || SdrFileReader::from_reader(Cursor::new(bytes.clone()), 30, SampleType::F32).build().unwrap();
+        let phase = build().read_next_chunk_phase_f32().unwrap().unwrap();
+        for (n, phase) in (0..30u8).zip(phase) {
+            assert!((phase - f32::from(n) * 0.1).abs() < 1e-5);
+        }
+
+        let mut reader = build();
+        let differences = std::iter::from_fn(|| reader.read_next_chunk_phase_diff_f32().unwrap()).flatten().collect::<Vec<_>>();
+        assert_eq!(differences.len(), 90);
+        assert!(differences[0].abs() < 1e-6);
+        // Including the differences across chunk boundaries and the wrap around from π to -π
+        assert!(differences[1..].iter().all(|difference| (difference - 0.1).abs() < 1e-5));
+    }
+
+    /// Fails to compile if `T` is not `Send`.
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn test_readers_are_send() {
+        assert_send::<SdrFileReader>();
+        assert_send::<SdrFileReader<Cursor<Vec<u8>>>>();
+        assert_send::<SdrWavReader>();
+        assert_send::<SdrFileWriter>();
+        assert_send::<InputFile>();
+        #[cfg(feature = "memmap")]
+        assert_send::<MmapSdrReader>();
+    }
+
+    #[test]
+    fn test_read_next_chunk_timed_f32() {
+        // 1200 samples at 2.4 MHz are 0.5 ms
+        let mut reader = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1200)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut starts = Vec::new();
+        while let Some((start, samples)) = reader.read_next_chunk_timed_f32().unwrap() {
+            assert_eq!(samples.len(), 1200);
+            starts.push(start);
+        }
+        assert_eq!(starts.len(), 13);
+        assert_eq!(starts[0], Duration::ZERO);
+        assert!(starts.iter().zip(0..).all(|(&start, n)| start == Duration::from_micros(500 * n)));
+
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0u8; 64]), 4, SampleType::I16).build().unwrap();
+        assert!(matches!(reader.read_next_chunk_timed_f32(), Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_lowpass() {
+        // A tone at 1 kHz below the 3 kHz cutoff and one at 10 kHz above it, at 48 kHz
+        let tone = |n: u32, freq: f64| Complex::from_polar(0.5, TAU * freq * f64::from(n) / 48_000.0);
+        let bytes = (0..4800)
+            .flat_map(|n| {
+                let sample = tone(n, 1000.0) + tone(n, 10_000.0);
+                [sample.re.to_le_bytes(), sample.im.to_le_bytes()].concat()
+            })
+            .collect::<Vec<_>>();
+        let build = |lowpass: Option<(f64, usize)>| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 256, SampleType::F64)
+            .sample_rate_hz(48_000)
+            .maybe_lowpass(lowpass)
+            .build();
+        let filtered = build(Some((3000.0, 101))).unwrap().read_all_complexf64().unwrap();
+        assert_eq!(filtered.len(), 4608);
+        // The output is delayed by 50 samples, and only the low tone is left once the filter has filled up
+        for (n, sample) in (0..).zip(&filtered).skip(100) {
+            assert!((sample - tone(n - 50, 1000.0)).norm() < 0.01);
+        }
+
+        assert!(matches!(build(Some((30_000.0, 101))), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!(build(Some((3000.0, 0))), Err(SdrReaderError::InvalidConfig(_))));
+        let result = SdrFileReader::from_reader(Cursor::new(bytes.clone()), 256, SampleType::F64).lowpass((3000.0, 101)).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let bytes = [0x00, 0x80, 0xFF, 0x7F, 0x00, 0x40, 0x00, 0xC0, 0x01];
+        let mut reader = SdrFileReader::from_bytes(bytes.to_vec(), 2, SampleType::I16)
+            .normalize(true)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        assert_eq!(reader.total_samples().unwrap(), 2);
+        let samples = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(samples, vec![Complex::new(-1.0, 32767.0 / 32768.0), Complex::new(0.5, -0.5)]);
+        assert!(matches!(reader.read_next_chunk_complexf32(), Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 1 })));
+    }
+
+    #[test]
+    fn test_planar_layout() {
+        // 10 I16 samples, the planar file has some unrelated bytes after the Q block
+        let interleaved = (0..40).collect::<Vec<u8>>();
+        let mut planar = interleaved.chunks(4).flat_map(|sample| sample[..2].to_vec()).collect::<Vec<_>>();
+        planar.extend(interleaved.chunks(4).flat_map(|sample| sample[2..].to_vec()));
+        planar.extend([0xFF; 6]);
+        let interleaved_path = write_temp_file("layout_interleaved", &interleaved);
+        let planar_path = write_temp_file("layout_planar", &planar);
+        let build = |file_path: &Path, layout| SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(4)
+            .sample_type(SampleType::I16)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .layout(layout)
+            .build()
+            .expect("Failed to create SdrFileReader");
+
+        let expected = build(&interleaved_path, Layout::Interleaved).read_all_complexf32().unwrap();
+        let mut reader = build(&planar_path, Layout::Planar { total_samples: 10 });
+        assert_eq!(reader.total_samples().unwrap(), 10);
+        assert_eq!(reader.read_all_complexf32().unwrap(), expected);
+        reader.seek_to_sample(3).unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), expected[3..7]);
+    }
+
+    #[test]
+    fn test_skip_silent_chunks() {
+        // Three quiet chunks of 4 samples, then a loud one, then silence again
+        let values = [[1i16, -2, 0, 3].repeat(6), [16_000, -16_000, 8000, 0].repeat(2), vec![0; 8]].concat();
+        let bytes = values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_bytes(bytes, 4, SampleType::I16).normalize(true).build().unwrap();
+        let chunk = reader.skip_silent_chunks(1e-4).unwrap().unwrap();
+        assert_eq!(chunk[0], Complex::new(16_000.0 / 32768.0, -16_000.0 / 32768.0));
+        assert_eq!(reader.sample_position().unwrap(), 16);
+        assert_eq!(reader.skip_silent_chunks(1e-4).unwrap(), None);
+    }
+
+    #[test]
+    fn test_footer_skip_bytes() {
+        // 100 I16 samples followed by a 128-byte footer that would decode to huge values
+        let samples = (0..200i16).collect::<Vec<_>>();
+        let mut bytes = samples.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        bytes.extend([0x7F; 128]);
+        let file_path = write_temp_file("footer", &bytes);
+        let build = || SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(16)
+            .sample_type(SampleType::I16)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .footer_skip_bytes(128)
+            .build()
+            .expect("Failed to create SdrFileReader");
+
+        let mut reader = build();
+        assert_eq!(reader.total_samples().unwrap(), 100);
+        reader.read_next_chunk_complexf32().unwrap();
+        assert_eq!(reader.remaining_samples().unwrap(), 84);
+        let mut reader = build();
+        let expected = samples.chunks_exact(2).map(|pair| Complex::new(f32::from(pair[0]), f32::from(pair[1]))).collect::<Vec<_>>();
+        assert_eq!(reader.read_all_complexf32().unwrap(), expected);
+        assert!(reader.read_next_chunk_complexf32().unwrap().is_none());
+
+        // A byte range that reaches into the footer ends at the footer
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(16)
+            .sample_type(SampleType::I16)
+            .footer_skip_bytes(128)
+            .byte_range((320, 1000))
+            .build()
+            .unwrap();
+        assert_eq!(reader.total_samples().unwrap(), 20);
+    }
+
+    #[test]
+    fn test_byte_range() {
+        // 25 I16 samples, the range covers samples 2 to 11 and half of sample 12
+        let bytes = (0..100).collect::<Vec<u8>>();
+        let file_path = write_temp_file("byte_range", &bytes);
+        let build = |trailing_bytes| SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(4)
+            .sample_type(SampleType::I16)
+            .trailing_bytes(trailing_bytes)
+            .byte_range((8, 50))
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let all = SdrFileReader::from_bytes(bytes.clone(), 25, SampleType::I16).build().unwrap().read_all_complexf32().unwrap();
+
+        let mut reader = build(TrailingPolicy::Drop);
+        assert_eq!(reader.total_samples().unwrap(), 10);
+        assert_eq!(reader.read_all_complexf32().unwrap(), all[2..10]);
+        reader.seek_to_sample(5).unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), all[7..11]);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+
+        let mut reader = build(TrailingPolicy::PartialChunk);
+        let chunks = reader.chunks_complexf32().collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 4);
+        assert!(matches!(chunks[..3], [Ok(_), Ok(_), Ok(_)]));
+        assert_eq!(chunks[2].as_ref().unwrap()[..], all[10..12]);
+        assert!(matches!(chunks[3], Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 2 })));
+    }
+
+    #[test]
+    fn test_gain() {
+        let bytes = (0..64).collect::<Vec<u8>>();
+        let read = |gain: Option<f32>, complex_gain: Option<Complex<f32>>| SdrFileReader::from_reader(Cursor::new(bytes.clone()), 8, SampleType::I16)
+            .normalize(true)
+            .maybe_gain(gain)
+            .maybe_complex_gain(complex_gain)
+            .build()
+            .unwrap()
+            .read_all_complexf32()
+            .unwrap();
+        let plain = read(None, None);
+        let doubled = read(Some(2.0), None);
+        assert_eq!(doubled, plain.iter().map(|sample| sample * 2.0).collect::<Vec<_>>());
+        // A gain of j rotates each sample by 90 degrees, combined with the gain of 2
+        let rotated = read(Some(2.0), Some(Complex::new(0.0, 1.0)));
+        assert_eq!(rotated, plain.iter().map(|sample| Complex::new(-2.0 * sample.im, 2.0 * sample.re)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_correct_iq_imbalance() {
+        // A tone with Q 20% stronger than I and 0.2 rad off from orthogonal
+        let bytes = (0..100_000u32)
+            .flat_map(|n| {
+                let theta = f64::from(n) * 0.3;
+                [theta.cos().to_le_bytes(), (1.2 * (theta + 0.2).sin()).to_le_bytes()].concat()
+            })
+            .collect::<Vec<_>>();
+        // The magnitude of the mirror image of the tone in the last chunk, relative to the tone
+        let image_ratio = |correct_iq_imbalance| {
+            let mut reader = SdrFileReader::from_reader(Cursor::new(bytes.clone()), 10_000, SampleType::F64)
+                .correct_iq_imbalance(correct_iq_imbalance)
+                .build()
+                .unwrap();
+            let chunks = reader.chunks_complexf64().collect::<Result<Vec<_>, _>>().unwrap();
+            let correlate = |step: f64| (0u32..)
+                .zip(&chunks[9])
+                .map(|(n, sample)| sample * Complex::from_polar(1.0, -step * f64::from(n)))
+                .sum::<Complex<f64>>()
+                .norm();
+            (correlate(-0.3) / correlate(0.3), reader.iq_imbalance())
+        };
+        let (uncorrected, imbalance) = image_ratio(false);
+        assert!(uncorrected > 0.1);
+        assert_eq!(imbalance, None);
+        let (corrected, imbalance) = image_ratio(true);
+        assert!(corrected < 0.01, "Image ratio {corrected}");
+        let imbalance = imbalance.unwrap();
+        assert!((imbalance.amplitude_ratio - 1.2).abs() < 0.01);
+        assert!((imbalance.phase_error - 0.2).abs() < 0.01);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_agc() {
+        // A tone that steps from an amplitude of 0.1 to 0.8 halfway through
+        let bytes = (0..64_000u32)
+            .flat_map(|n| {
+                let amplitude = if n < 32_000 { 0.1 } else { 0.8 };
+                let sample = Complex::from_polar(amplitude, f64::from(n) * 0.3);
+                [sample.re.to_le_bytes(), sample.im.to_le_bytes()].concat()
+            })
+            .collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 1000, SampleType::F64)
+            .agc((0.5, 0.5, 0.2))
+            .build()
+            .unwrap();
+        let rms = |chunk: &[Complex<f64>]| (chunk.iter().map(Complex::norm_sqr).sum::<f64>() / chunk.len() as f64).sqrt();
+        let chunks = reader.chunks_complexf64().collect::<Result<Vec<_>, _>>().unwrap();
+        assert!((rms(&chunks[0]) - 0.5).abs() > 0.1);
+        assert!((rms(&chunks[31]) - 0.5).abs() < 1e-3);
+        // The jump in level overshoots, then the gain catches up
+        assert!(rms(&chunks[32]) > 1.0);
+        assert!((rms(&chunks[63]) - 0.5).abs() < 1e-3);
+        assert!((reader.agc_gain() - 0.5 / 0.8).abs() < 1e-3);
+
+        let result = SdrFileReader::from_reader(Cursor::new(Vec::new()), 1000, SampleType::F64).agc((0.5, 0.0, 0.2)).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_occupancy() {
+        // A burst of 250 strong samples in 1000 samples of weak noise
+        let bytes = (0..1000i16)
+            .flat_map(|n| {
+                let (i, q) = if (500..750).contains(&n) { (20_000i16, -15_000i16) } else { (n % 7 - 3, 2 - n % 5) };
+                [i.to_le_bytes(), q.to_le_bytes()].concat()
+            })
+            .collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 100, SampleType::I16).normalize(true).build().unwrap();
+        assert!((reader.occupancy(0.1).unwrap() - 0.25).abs() < f32::EPSILON);
+        assert!(reader.occupancy(0.1).unwrap().abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_estimate_noise_floor() {
+        // Complex Gaussian noise with a power of 2e-4 (-37 dB) from a xorshift generator and the Box-Muller transform,
+        // with a strong tone in 3% of the samples
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut uniform = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            #[allow(clippy::cast_precision_loss)]
+            let value = (state >> 11) as f64 / (1u64 << 53) as f64;
+            value
+        };
+        let bytes = (0..8000u32)
+            .flat_map(|n| {
+                let noise = Complex::from_polar(0.01 * (-2.0 * (1.0 - uniform()).ln()).sqrt(), std::f64::consts::TAU * uniform());
+                let tone = if n % 100 < 3 { Complex::from_polar(1.0, f64::from(n)) } else { Complex::new(0.0, 0.0) };
+                let sample: Complex<f64> = noise + tone;
+                [sample.re.to_le_bytes(), sample.im.to_le_bytes()].concat()
+            })
+            .collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_bytes(bytes, 1000, SampleType::F64).build().unwrap();
+        reader.skip_samples(500).unwrap();
+        let noise_floor = reader.estimate_noise_floor_db(4).unwrap();
+        assert!((noise_floor - 10.0 * 2e-4f32.log10()).abs() < 0.5, "{noise_floor} dB");
+        // The read position is restored
+        assert_eq!(reader.sample_position().unwrap(), 500);
+
+        assert!(matches!(reader.estimate_noise_floor_db(0), Err(SdrReaderError::InvalidConfig(_))));
+        reader.skip_samples(10_000).unwrap();
+        assert!(reader.estimate_noise_floor_db(4).unwrap().is_infinite());
+    }
+
+    #[test]
+    fn test_looks_like_float() {
+        // The F32 capture is recognized even when it's wrongly configured as I16
+        let mut reader = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(4)
+            .sample_type(SampleType::I16)
+            .build()
+            .unwrap();
+        let first = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert!(reader.looks_like_float().unwrap());
+        let second = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        reader.seek_to_sample(4).unwrap();
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), second);
+        assert_ne!(first, second);
+
+        let bytes = (0..2048i16).flat_map(|n| (n * 13 - 9000).to_le_bytes()).collect::<Vec<_>>();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 4, SampleType::I16).build().unwrap();
+        assert!(!reader.looks_like_float().unwrap());
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0; 64]), 4, SampleType::F32).build().unwrap();
+        assert!(!reader.looks_like_float().unwrap());
+    }
+
+    #[test]
+    fn test_file_shorter_than_one_chunk() {
+        let bytes = (0..200u8).flat_map(|n| f32::from(n).to_le_bytes()).collect::<Vec<_>>();
+        let file_path = write_temp_file("shorter_than_one_chunk", &bytes);
+        let build = |trailing_bytes| SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .trailing_bytes(trailing_bytes)
+            .build()
+            .expect("Failed to create SdrFileReader");
+
+        let mut reader = build(TrailingPolicy::PartialChunk);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap().len(), 100);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+        assert_eq!(build(TrailingPolicy::Drop).read_next_chunk_complexf32().unwrap(), None);
+    }
+
+    #[test]
+    fn test_decimation_factor() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let build = |decimation_factor| SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(10)
+            .sample_type(SampleType::F32)
+            .decimation_factor(decimation_factor)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let all_samples = build(1).chunks_complexf32().take(10).flat_map(Result::unwrap).collect::<Vec<_>>();
+
+        // The chunk size is not a multiple of the factor, so the kept indices have to carry over between chunks
+        let mut reader = build(4);
+        let chunks = reader.chunks_complexf32().take(10).map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 2, 3, 2, 3, 2, 3, 2, 3, 2]);
+        let expected = all_samples.iter().step_by(4).copied().collect::<Vec<_>>();
+        assert_eq!(chunks.concat(), expected);
+
+        let mut interleaved = Vec::new();
+        let mut reader = build(4);
+        assert_eq!(reader.read_next_chunk_interleaved_f32(&mut interleaved).unwrap(), Some(3));
+        assert_eq!(interleaved, vec![all_samples[0].re, all_samples[0].im, all_samples[4].re, all_samples[4].im, all_samples[8].re, all_samples[8].im]);
+    }
+
+    #[test]
+    fn test_decimation_factor_above_chunk_size() {
+        let bytes = vec![0; 400];
+        let result = SdrFileReader::from_reader(Cursor::new(bytes.clone()), 10, SampleType::I16).decimation_factor(11).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+        // Every chunk keeps exactly one sample
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 10, SampleType::I16).decimation_factor(10).build().unwrap();
+        let chunk_lens = reader.chunks_complexf32().map(|chunk| chunk.unwrap().len()).collect::<Vec<_>>();
+        assert_eq!(chunk_lens, vec![1; 10]);
+    }
+
+    #[test]
+    fn test_max_samples() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .max_samples(1500)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let chunk_lens = reader.chunks_complexf32().map(|chunk| chunk.unwrap().len()).collect::<Vec<_>>();
+        assert_eq!(chunk_lens, vec![1024, 476]);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+
+        // A limit beyond the end of the file has no effect
+        let mut reader = SdrFileReader::from_reader(Cursor::new(vec![0u8; 10]), 4, SampleType::U8)
+            .max_samples(100)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let chunk_lens = reader.chunks_complexf64().map(|chunk| chunk.unwrap().len()).collect::<Vec<_>>();
+        assert_eq!(chunk_lens, vec![4]);
+    }
+
+    #[test]
+    fn test_chunk_iterators() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let expected_samples = std::fs::metadata(file_path).unwrap().len() / SampleType::F32.sample_len() as u64;
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let chunks = reader.chunks_complexf32().collect::<Result<Vec<_>, _>>().unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.len() == 1024));
+        assert_eq!(chunks.iter().map(Vec::len).sum::<usize>() as u64, expected_samples);
+
+        let mut reader = SdrFileReader::builder()
+            .file_path(file_path)
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let samples = reader.chunks_complexf64().flatten().flatten().count();
+        assert_eq!(samples as u64, expected_samples);
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_compare_f32_f64_readings() {
+        let file_path = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+        let mut reader_f32 = SdrFileReader::builder()
+            .file_path(file_path)
             .samples_per_chunk(1024)
             .sample_type(SampleType::F32)
             .build()
@@ -216,4 +4191,4 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+}
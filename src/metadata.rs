@@ -0,0 +1,279 @@
+//! Capture metadata such as center frequency and sample rate.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use bon::bon;
+use crate::{InputFile, SampleType, SdrFileReader, SdrReaderError, TrailingPolicy};
+
+/// Information about how a capture was recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureMeta {
+    /// The time the capture was started (UTC), if known
+    pub timestamp: Option<SystemTime>,
+    /// The center frequency the SDR was tuned to in Hz
+    pub center_freq_hz: u64,
+    /// The sample rate in Hz
+    pub sample_rate_hz: u64,
+}
+
+/// Parse the capture metadata from a file name in the format gqrx uses for its recordings,
+/// e.g. `gqrx_20240929_015218_580206500_2400000_fc.raw`.
+/// The name encodes the date and time (UTC), the center frequency in Hz and the sample rate in Hz.
+///
+/// Returns `None` if the file name does not match that pattern.
+///
+/// # Example
+/// ```
+/// use std::path::Path;
+/// use sdr_iq_file_reader::parse_gqrx_filename;
+/// let meta = parse_gqrx_filename(Path::new("gqrx_20240929_015218_580206500_2400000_fc.raw")).unwrap();
+/// assert_eq!(meta.center_freq_hz, 580_206_500);
+/// assert_eq!(meta.sample_rate_hz, 2_400_000);
+/// ```
+#[must_use]
+pub fn parse_gqrx_filename(path: &Path) -> Option<CaptureMeta> {
+    let stem = path.file_stem()?.to_str()?;
+    let mut parts = stem.split('_');
+    if parts.next()? != "gqrx" {
+        return None;
+    }
+    let date = parts.next()?;
+    let time = parts.next()?;
+    let center_freq_hz = parse_digits(parts.next()?)?;
+    let sample_rate_hz = parse_digits(parts.next()?)?;
+    // Checking the digits first keeps the slicing below on char boundaries
+    let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    if date.len() != 8 || time.len() != 6 || !is_digits(date) || !is_digits(time) {
+        return None;
+    }
+
+    let year = i64::try_from(parse_digits(&date[0..4])?).ok()?;
+    let month = parse_digits(&date[4..6])?;
+    let day = parse_digits(&date[6..8])?;
+    let hour = parse_digits(&time[0..2])?;
+    let minute = parse_digits(&time[2..4])?;
+    let second = parse_digits(&time[4..6])?;
+
+    Some(CaptureMeta {
+        timestamp: Some(utc_timestamp(year, month, day, hour, minute, second)?),
+        center_freq_hz,
+        sample_rate_hz,
+    })
+}
+
+/// The sample type encoded by the suffix of a gqrx file name, e.g. `F32` for the `fc` (complex float) of
+/// `gqrx_20240929_015218_580206500_2400000_fc.raw`. Returns `None` for other suffixes.
+fn gqrx_sample_type(path: &Path) -> Option<SampleType> {
+    match path.file_stem()?.to_str()?.rsplit('_').next()? {
+        "fc" => Some(SampleType::F32),
+        _ => None,
+    }
+}
+
+#[bon]
+impl SdrFileReader {
+    /// Create a new `SdrFileReader` for a gqrx recording.
+    /// Unlike [`SdrFileReader::builder`], this fails if the file name doesn't follow the gqrx naming scheme,
+    /// so the metadata, sample rate and center frequency of the reader are always known.
+    /// The sample type is taken from the suffix of the file name, `fc` for complex float samples.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let reader = SdrFileReader::from_gqrx_filename("gqrx_20240929_015218_580206500_2400000_fc.raw", 1024)
+    ///     .build()
+    ///     .expect("Failed to open gqrx recording");
+    /// assert_eq!(reader.sample_rate_hz(), Some(2_400_000));
+    /// assert_eq!(reader.sample_type(), SampleType::F32);
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidMetadata` if the file name doesn't follow the gqrx naming scheme,
+    ///   or its suffix is not a known sample type and `sample_type` is not set
+    /// - `SdrReaderError::Open` if the file could not be opened
+    #[builder(finish_fn = build)]
+    pub fn from_gqrx_filename(
+        #[builder(start_fn)] file_path: impl AsRef<Path>,
+        #[builder(start_fn)] samples_per_chunk: usize,
+        /// The type of the samples, instead of the one given by the suffix of the file name.
+        sample_type: Option<SampleType>,
+        /// What happens to the trailing bytes that don't make up a whole chunk, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::trailing_bytes).
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+    ) -> Result<Self, SdrReaderError> {
+        let file_path = file_path.as_ref();
+        let metadata = parse_gqrx_filename(file_path)
+            .ok_or_else(|| SdrReaderError::InvalidMetadata(format!("{} is not a gqrx file name", file_path.display())))?;
+        let sample_type = sample_type.or_else(|| gqrx_sample_type(file_path)).ok_or_else(|| {
+            SdrReaderError::InvalidMetadata(format!("{} has no known sample type suffix", file_path.display()))
+        })?;
+        SdrFileReader::from_reader(InputFile::open(file_path, false)?, samples_per_chunk, sample_type)
+            .trailing_bytes(trailing_bytes)
+            .metadata(metadata)
+            .build()
+            .map(SdrFileReader::with_seek)
+    }
+}
+
+/// Parse an ISO 8601 UTC timestamp like `2024-09-29T01:52:18.123Z`, as used by `SigMF`.
+pub(crate) fn parse_iso8601_utc(timestamp: &str) -> Option<SystemTime> {
+    let timestamp = timestamp.strip_suffix('Z')?;
+    let (date, time) = timestamp.split_once('T')?;
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut date_parts = date.split('-');
+    let year = i64::try_from(parse_digits(date_parts.next()?)?).ok()?;
+    let month = parse_digits(date_parts.next()?)?;
+    let day = parse_digits(date_parts.next()?)?;
+    let mut time_parts = time.split(':');
+    let hour = parse_digits(time_parts.next()?)?;
+    let minute = parse_digits(time_parts.next()?)?;
+    let second = parse_digits(time_parts.next()?)?;
+    if date_parts.next().is_some() || time_parts.next().is_some() {
+        return None;
+    }
+
+    let mut nanos = 0;
+    if !fraction.is_empty() {
+        // Checking the digits first keeps the truncation below on a char boundary
+        if !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        // Only nanosecond precision is kept
+        let digits = &fraction[..fraction.len().min(9)];
+        let scale = 10u32.pow(u32::try_from(9 - digits.len()).ok()?);
+        nanos = u32::try_from(parse_digits(digits)?).ok()? * scale;
+    }
+    Some(utc_timestamp(year, month, day, hour, minute, second)? + Duration::from_nanos(u64::from(nanos)))
+}
+
+/// Format a `SystemTime` as an ISO 8601 UTC timestamp like `2024-09-29T01:52:18.123Z`, the inverse of [`parse_iso8601_utc`].
+/// The fraction of a second is only written if it is not zero. Returns `None` for times before 1970.
+pub(crate) fn format_iso8601_utc(time: SystemTime) -> Option<String> {
+    let since_epoch = time.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days(i64::try_from(seconds / 86_400).ok()?);
+    let (hour, minute, second) = (seconds % 86_400 / 3_600, seconds % 3_600 / 60, seconds % 60);
+    let mut timestamp = format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}");
+    if since_epoch.subsec_nanos() > 0 {
+        let fraction = format!("{:09}", since_epoch.subsec_nanos());
+        timestamp = format!("{timestamp}.{}", fraction.trim_end_matches('0'));
+    }
+    Some(timestamp + "Z")
+}
+
+/// Convert a UTC date and time into a `SystemTime`, returning `None` for out-of-range or pre-1970 values.
+fn utc_timestamp(year: i64, month: u64, day: u64, hour: u64, minute: u64, second: u64) -> Option<SystemTime> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// Parse a string made up only of ASCII digits (no sign, no whitespace).
+fn parse_digits(digits: &str) -> Option<u64> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// The number of days between 1970-01-01 and the given date in the proleptic Gregorian calendar.
+#[allow(clippy::cast_possible_wrap)]
+fn days_from_civil(year: i64, month: u64, day: u64) -> i64 {
+    // Shift the year so it starts in March, which puts the leap day at the end of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year as i64;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date in the proleptic Gregorian calendar `days` days after 1970-01-01, the inverse of [`days_from_civil`].
+#[allow(clippy::cast_sign_loss)]
+fn civil_from_days(days: i64) -> (i64, u64, u64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u64;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 } as u64;
+    // The year starts in March, so January and February belong to the next calendar year
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gqrx_filename() {
+        let meta = parse_gqrx_filename(Path::new("/captures/gqrx_20240929_015218_580206500_2400000_fc.raw"))
+            .expect("Failed to parse gqrx file name");
+        assert_eq!(meta.center_freq_hz, 580_206_500);
+        assert_eq!(meta.sample_rate_hz, 2_400_000);
+        // 2024-09-29T01:52:18Z
+        assert_eq!(meta.timestamp, Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_727_574_738)));
+        // A multi-byte character with the same byte length as the date must not panic
+        assert_eq!(parse_gqrx_filename(Path::new("gqrx_202é929_015218_1_1_fc.raw")), None);
+    }
+
+    #[test]
+    fn test_parse_non_gqrx_filename() {
+        assert_eq!(parse_gqrx_filename(Path::new("capture.raw")), None);
+        assert_eq!(parse_gqrx_filename(Path::new("gqrx_20240929_015218_fc.raw")), None);
+        assert_eq!(parse_gqrx_filename(Path::new("gqrx_20241329_015218_580206500_2400000_fc.raw")), None);
+        assert_eq!(parse_gqrx_filename(Path::new("gqrx_20240929_015218_+580206500_2400000_fc.raw")), None);
+    }
+
+    #[test]
+    fn test_from_gqrx_filename_rejects_other_names() {
+        let result = SdrFileReader::from_gqrx_filename("capture.raw", 1024).sample_type(SampleType::F32).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidMetadata(_))));
+    }
+
+    #[test]
+    fn test_gqrx_sample_type() {
+        assert_eq!(gqrx_sample_type(Path::new("gqrx_20240929_015218_580206500_2400000_fc.raw")), Some(SampleType::F32));
+        assert_eq!(gqrx_sample_type(Path::new("gqrx_20240929_015218_580206500_2400000_xy.raw")), None);
+        let result = SdrFileReader::from_gqrx_filename("gqrx_20240929_015218_580206500_2400000_xy.raw", 1024).build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidMetadata(_))));
+    }
+
+    #[test]
+    fn test_parse_iso8601_utc() {
+        assert_eq!(parse_iso8601_utc("2024-09-29T01:52:18Z"), Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_727_574_738)));
+        assert_eq!(
+            parse_iso8601_utc("2024-09-29T01:52:18.25Z"),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_millis(1_727_574_738_250))
+        );
+        assert_eq!(parse_iso8601_utc("2024-09-29 01:52:18"), None);
+        assert_eq!(parse_iso8601_utc("2024-09-29T01:52:18.12345678éZ"), None);
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        for days in [0, 11_016, 11_017, 19_995, -1] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+
+    #[test]
+    fn test_format_iso8601_utc() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_727_574_738_250);
+        assert_eq!(format_iso8601_utc(time).as_deref(), Some("2024-09-29T01:52:18.25Z"));
+        assert_eq!(format_iso8601_utc(SystemTime::UNIX_EPOCH).as_deref(), Some("1970-01-01T00:00:00Z"));
+        assert_eq!(parse_iso8601_utc(&format_iso8601_utc(time).unwrap()), Some(time));
+        assert_eq!(format_iso8601_utc(SystemTime::UNIX_EPOCH - Duration::from_secs(1)), None);
+    }
+}
@@ -0,0 +1,323 @@
+//! Reading samples from a memory-mapped file.
+
+use std::ops::Range;
+use std::path::Path;
+use bon::bon;
+use memmap2::Mmap;
+use num_complex::Complex;
+use crate::decode::DecodeOptions;
+use crate::input::open_file;
+use crate::{Endianness, SampleType, SdrReaderError, TrailingPolicy};
+
+/// Reads samples from a file that is memory-mapped as a whole, instead of through a `BufReader`.
+/// Chunks are decoded directly from the mapped memory, and seeking to any sample is just an index into it,
+/// which makes this reader a good fit for random access into multi-gigabyte captures.
+///
+/// The bytes at the end of the file that don't make up a whole chunk are handled like in [`crate::SdrFileReader`],
+/// according to the `trailing_bytes` option.
+///
+/// # Example
+/// ```
+/// use sdr_iq_file_reader::{MmapSdrReader, SampleType};
+/// let mut reader = MmapSdrReader::builder()
+///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+///     .samples_per_chunk(1024)
+///     .sample_type(SampleType::F32)
+///     .build()
+///     .expect("Failed to map file");
+/// reader.seek_to_sample(reader.total_samples() / 2);
+/// let samples = reader.read_next_chunk_complexf32().unwrap().unwrap();
+/// ```
+pub struct MmapSdrReader {
+    mmap: Mmap,
+    samples_per_chunk: usize,
+    decode: DecodeOptions,
+    trailing_policy: TrailingPolicy,
+    position: usize,
+    /// Whether the stray bytes at the end of the file have been reported since the last seek
+    trailing_reported: bool,
+}
+
+#[bon]
+impl MmapSdrReader {
+    /// Create a new `MmapSdrReader` by mapping the file at `file_path` into memory.
+    ///
+    /// The file must not be modified or truncated by another process while it is mapped,
+    /// as the mapped memory would change underneath the reader.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `samples_per_chunk` is 0
+    /// - `SdrReaderError::InvalidConfig` if `normalize_with` is not positive and finite
+    /// - `SdrReaderError::InvalidConfig` if `bit_shift` is not less than the number of bits of a component
+    /// - `SdrReaderError::InvalidConfig` if the sample type is `F16` and the `half` feature is disabled
+    /// - `SdrReaderError::Open` if the file could not be opened
+    /// - `SdrReaderError::Io` if the file could not be mapped
+    #[builder]
+    pub fn new(
+        file_path: impl AsRef<Path>,
+        samples_per_chunk: usize,
+        sample_type: SampleType,
+        /// The byte order of multi-byte samples. Defaults to [`Endianness::Little`].
+        #[builder(default)] endianness: Endianness,
+        /// Subtract the midpoint (127.5 for `U8`, 32767.5 for `U16`) from unsigned samples so they are centered around zero.
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0] by dividing by the full-scale value of the sample type.
+        /// Float sample types are passed through unchanged.
+        #[builder(default)] normalize: bool,
+        /// Normalize by dividing by this value instead of the full-scale value of the sample type, implies `normalize`.
+        /// Unlike `normalize`, this also applies to float sample types. Must be positive and finite.
+        normalize_with: Option<f64>,
+        /// Shift integer components right by this many bits before the conversion, e.g. 4 to drop the noise bits
+        /// of 12-bit samples left-justified in 16-bit words. The shift is arithmetic for signed types.
+        /// Must be less than the number of bits of a component. Defaults to 0.
+        #[builder(default)] bit_shift: u8,
+        /// Interpret signed integer samples as offset binary instead of two's complement, as output by some ADCs:
+        /// the most significant bit of each component is flipped before the conversion. Applied before `bit_shift`.
+        #[builder(default)] offset_binary: bool,
+        /// Swap the I and Q components of each sample, which mirrors the spectrum.
+        /// Useful for hardware or recordings that store Q before I.
+        #[builder(default)] swap_iq: bool,
+        /// Negate the imaginary part of each sample, which mirrors the spectrum. Applied after `swap_iq`.
+        #[builder(default)] conjugate: bool,
+        /// What happens to the bytes at the end of the file that don't make up a whole chunk. Defaults to [`TrailingPolicy::Drop`].
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+    ) -> Result<Self, SdrReaderError> {
+        if samples_per_chunk == 0 {
+            return Err(SdrReaderError::InvalidConfig("samples_per_chunk must not be 0".to_string()));
+        }
+        let file = open_file(file_path.as_ref())?;
+        // SAFETY: The file is only read, and the caller is told not to modify it while it is mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(MmapSdrReader {
+            mmap,
+            samples_per_chunk,
+            decode: DecodeOptions {
+                sample_type,
+                endianness,
+                center_unsigned,
+                normalize: normalize || normalize_with.is_some(),
+                swap_iq,
+                conjugate,
+                full_scale: normalize_with,
+                bit_shift,
+                offset_binary,
+                byte_table: None,
+            }
+            .validate()?
+            .with_byte_table(),
+            trailing_policy: trailing_bytes,
+            position: 0,
+            trailing_reported: false,
+        })
+    }
+}
+
+impl MmapSdrReader {
+    /// The total number of samples in the file.
+    /// Trailing bytes that don't make up a whole sample are not counted.
+    #[must_use]
+    pub fn total_samples(&self) -> u64 {
+        (self.mmap.len() / self.decode.sample_type.sample_len()) as u64
+    }
+
+    /// The index of the next sample that will be read.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.position as u64
+    }
+
+    /// The number of samples left to read from the current position to the end of the file.
+    #[must_use]
+    pub fn remaining_samples(&self) -> u64 {
+        self.total_samples() - self.position()
+    }
+
+    /// Move the read position to the sample at index `sample`.
+    /// Positions past the end of the file are clamped to the end.
+    pub fn seek_to_sample(&mut self, sample: u64) {
+        let total_samples = self.mmap.len() / self.decode.sample_type.sample_len();
+        self.position = usize::try_from(sample).unwrap_or(usize::MAX).min(total_samples);
+        self.trailing_reported = false;
+    }
+
+    /// The byte range of the next chunk in the mapped file, advancing the position past it.
+    /// Returns `None` if no chunk is left, and reports the trailing bytes like `SdrFileReader` does.
+    fn next_chunk_range(&mut self) -> Result<Option<Range<usize>>, SdrReaderError> {
+        if self.trailing_reported {
+            return Ok(None);
+        }
+        let sample_len = self.decode.sample_type.sample_len();
+        let start = self.position * sample_len;
+        let available = self.mmap.len() - start;
+        let len = match self.trailing_policy.chunk_len(available, self.samples_per_chunk * sample_len, sample_len) {
+            Ok(0) if self.trailing_policy == TrailingPolicy::PartialChunk && available > 0 => {
+                self.trailing_reported = true;
+                return Err(SdrReaderError::UnexpectedPartialSample { bytes_left: available });
+            }
+            Ok(0) => return Ok(None),
+            Ok(len) => len,
+            Err(why) => {
+                // The incomplete chunk counts as consumed, like in `SdrFileReader`
+                self.trailing_reported = true;
+                return Err(why);
+            }
+        };
+        self.position += len / sample_len;
+        Ok(Some(start..start + len))
+    }
+
+    /// Read the next chunk of samples as Complex<f32>.
+    ///
+    /// # Warning
+    /// If you have set the sample type to `SampleType::F64`, you should use `read_next_chunk_complexf64` instead. Otherwise, the values will be truncated and you will lose accuracy.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::UnexpectedPartialSample` if the file ends in the middle of a sample with `TrailingPolicy::PartialChunk`
+    /// - `SdrReaderError::UnexpectedPartialChunk` if the file ends in the middle of a chunk with `TrailingPolicy::Error`
+    pub fn read_next_chunk_complexf32(&mut self) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        let Some(range) = self.next_chunk_range()? else {
+            return Ok(None);
+        };
+        let mut samples = Vec::with_capacity(range.len() / self.decode.sample_type.sample_len());
+        self.decode.decode(&self.mmap[range], &mut samples);
+        Ok(Some(samples))
+    }
+
+    /// Read the next chunk of samples as Complex<f64>.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - See [`MmapSdrReader::read_next_chunk_complexf32`]
+    pub fn read_next_chunk_complexf64(&mut self) -> Result<Option<Vec<Complex<f64>>>, SdrReaderError> {
+        let Some(range) = self.next_chunk_range()? else {
+            return Ok(None);
+        };
+        let mut samples = Vec::with_capacity(range.len() / self.decode.sample_type.sample_len());
+        self.decode.decode(&self.mmap[range], &mut samples);
+        Ok(Some(samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SdrFileReader;
+
+    const FILE_PATH: &str = "gqrx_20240929_015218_580206500_2400000_fc.raw";
+
+    #[test]
+    fn test_matches_buffered_reader() {
+        let mut buffered = SdrFileReader::builder()
+            .file_path(FILE_PATH)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut mapped = MmapSdrReader::builder()
+            .file_path(FILE_PATH)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to map file");
+        assert_eq!(mapped.total_samples(), buffered.total_samples().unwrap());
+
+        let third_chunk = buffered.chunks_complexf64().nth(2).unwrap().unwrap();
+        mapped.seek_to_sample(2000);
+        assert_eq!(mapped.read_next_chunk_complexf64().unwrap().unwrap(), third_chunk);
+        assert_eq!(mapped.position(), 3000);
+        assert_eq!(mapped.read_next_chunk_complexf32().unwrap(), buffered.read_next_chunk_complexf32().unwrap());
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        // 10 I16 samples plus one stray byte
+        let file_path = std::env::temp_dir().join("sdr_iq_file_reader_mmap_trailing_bytes.raw");
+        std::fs::write(&file_path, (0..41).collect::<Vec<u8>>()).unwrap();
+        let build = |trailing_bytes| MmapSdrReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(4)
+            .sample_type(SampleType::I16)
+            .trailing_bytes(trailing_bytes)
+            .build()
+            .expect("Failed to map file");
+        let read_chunks = |reader: &mut MmapSdrReader| {
+            std::iter::from_fn(|| reader.read_next_chunk_complexf32().transpose()).map(|chunk| chunk.map(|chunk| chunk.len())).collect::<Vec<_>>()
+        };
+
+        let mut reader = build(TrailingPolicy::PartialChunk);
+        assert_eq!(reader.total_samples(), 10);
+        let chunk_lens = read_chunks(&mut reader);
+        assert!(matches!(chunk_lens[..], [Ok(4), Ok(4), Ok(2), Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 1 })]));
+        assert_eq!(reader.remaining_samples(), 0);
+        assert!(reader.read_next_chunk_complexf32().unwrap().is_none());
+
+        let mut reader = build(TrailingPolicy::Drop);
+        let chunk_lens = read_chunks(&mut reader);
+        assert!(matches!(chunk_lens[..], [Ok(4), Ok(4)]));
+        reader.seek_to_sample(100);
+        assert_eq!(reader.position(), 10);
+
+        let mut reader = build(TrailingPolicy::Error);
+        let chunk_lens = read_chunks(&mut reader);
+        assert!(matches!(chunk_lens[..], [Ok(4), Ok(4), Err(SdrReaderError::UnexpectedPartialChunk { bytes_left: 9 })]));
+        reader.seek_to_sample(4);
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap().len(), 4);
+        std::fs::remove_file(&file_path).unwrap();
+    }
+
+    #[test]
+    fn test_decode_options() {
+        let file_path = std::env::temp_dir().join(format!("sdr_iq_file_reader_mmap_decode_options_{}.raw", std::process::id()));
+        let bytes = [1i16, -2, 300, -400, 5000, -6000, 7, 8].iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        std::fs::write(&file_path, &bytes).unwrap();
+        let mut buffered = SdrFileReader::from_reader(std::io::Cursor::new(bytes), 2, SampleType::I16)
+            .swap_iq(true)
+            .conjugate(true)
+            .normalize_with(2048.0)
+            .bit_shift(4)
+            .offset_binary(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut mapped = MmapSdrReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(2)
+            .sample_type(SampleType::I16)
+            .swap_iq(true)
+            .conjugate(true)
+            .normalize_with(2048.0)
+            .bit_shift(4)
+            .offset_binary(true)
+            .build()
+            .expect("Failed to map file");
+        while let Some(samples) = mapped.read_next_chunk_complexf32().unwrap() {
+            assert_eq!(Some(samples), buffered.read_next_chunk_complexf32().unwrap());
+        }
+        assert_eq!(buffered.read_next_chunk_complexf32().unwrap(), None);
+        drop(mapped);
+        std::fs::remove_file(&file_path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let result = MmapSdrReader::builder()
+            .file_path(FILE_PATH)
+            .samples_per_chunk(0)
+            .sample_type(SampleType::F32)
+            .build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+        let result = MmapSdrReader::builder()
+            .file_path(FILE_PATH)
+            .samples_per_chunk(1000)
+            .sample_type(SampleType::F32)
+            .normalize_with(f64::NAN)
+            .build();
+        assert!(matches!(result, Err(SdrReaderError::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,83 @@
+//! Decoding chunks on multiple threads with rayon.
+
+use std::io::Read;
+use num_complex::Complex;
+use rayon::prelude::*;
+use crate::{SdrFileReader, SdrReaderError};
+
+/// The number of samples each rayon task decodes.
+const SAMPLES_PER_TASK: usize = 4096;
+
+impl<R: Read> SdrFileReader<R> {
+    /// Read the next chunk of samples as Complex<f32>, decoding it on multiple threads.
+    ///
+    /// The raw bytes are read serially, then decoded in parallel in blocks of a few thousand samples.
+    /// The output is identical to [`SdrFileReader::read_next_chunk_complexf32`], but decoding large chunks
+    /// (especially of `F64` and `I24` samples, where each sample takes more work) is faster.
+    /// Small chunks don't benefit, as the work is split into blocks of 4096 samples.
+    ///
+    /// # Returns
+    /// - `Ok(Some(samples))` if there are samples in the chunk
+    /// - `Ok(None)` if the end of the file is reached
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`](crate::TrailingPolicy)
+    pub fn read_next_chunk_complexf32_par(&mut self) -> Result<Option<Vec<Complex<f32>>>, SdrReaderError> {
+        let Some(len) = self.read_chunk_bytes()? else {
+            return Ok(None);
+        };
+        let sample_len = self.decode.sample_type.sample_len();
+        let mut samples = vec![Complex::new(0.0, 0.0); len / sample_len];
+        let decode = &self.decode;
+        samples
+            .par_chunks_mut(SAMPLES_PER_TASK)
+            .zip(self.buffer[..len].par_chunks(SAMPLES_PER_TASK * sample_len))
+            .for_each(|(samples, bytes)| {
+                let mut slots = samples.iter_mut();
+                decode.decode_with(bytes, |i, q| {
+                    if let Some(slot) = slots.next() {
+                        *slot = Complex::new(i, q);
+                    }
+                });
+            });
+        self.process(&mut samples);
+        self.pad(&mut samples);
+        Ok(Some(samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::{SampleType, TrailingPolicy};
+
+    #[test]
+    fn test_par_matches_serial() {
+        let bytes = (0..60_000u32).map(|n| (n * 7 % 251) as u8).collect::<Vec<_>>();
+        let formats: [fn() -> SampleType; 3] = [|| SampleType::I24, || SampleType::F64, || SampleType::U8];
+        for sample_type in formats {
+            let build = || SdrFileReader::from_reader(Cursor::new(bytes.clone()), 10_000, sample_type())
+                .trailing_bytes(TrailingPolicy::PartialChunk)
+                .normalize(true)
+                .build()
+                .expect("Failed to create SdrFileReader");
+            let (mut serial, mut parallel) = (build(), build());
+            loop {
+                let expected = serial.read_next_chunk_complexf32();
+                let samples = parallel.read_next_chunk_complexf32_par();
+                match (expected, samples) {
+                    (Ok(Some(expected)), Ok(Some(samples))) => {
+                        let to_bits = |samples: &[Complex<f32>]| samples.iter().map(|s| (s.re.to_bits(), s.im.to_bits())).collect::<Vec<_>>();
+                        assert_eq!(to_bits(&samples), to_bits(&expected));
+                    }
+                    (Ok(None), Ok(None)) => break,
+                    (Err(SdrReaderError::UnexpectedPartialSample { .. }), Err(SdrReaderError::UnexpectedPartialSample { .. })) => {}
+                    _ => panic!("The parallel and serial reads differ"),
+                }
+            }
+        }
+    }
+}
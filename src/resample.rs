@@ -0,0 +1,157 @@
+//! Streaming rational sample rate conversion.
+
+use std::f64::consts::PI;
+use num_complex::Complex;
+use num_traits::{Float, FromPrimitive};
+
+/// The half length of the prototype filter in multiples of `max(up, down)`, the same as scipy's `resample_poly`.
+const HALF_LEN_FACTOR: usize = 10;
+
+/// The largest numerator or denominator of the reduced ratio. The prototype filter has about
+/// `2 * HALF_LEN_FACTOR * max(up, down)` taps, so nearly coprime rates would need millions of taps.
+pub(crate) const MAX_RATIO_TERM: usize = 10_000;
+
+/// The shape parameter of the Kaiser window of the prototype filter.
+const KAISER_BETA: f64 = 5.0;
+
+/// A polyphase resampler that converts the sample rate by the rational factor `up / down`.
+///
+/// Conceptually, the input is upsampled by `up` (inserting zeros), low-pass filtered and downsampled by `down`.
+/// The polyphase form only computes the filter outputs that are kept, using every `up`th tap of the prototype filter.
+/// The last input samples are kept between calls, so chunked input resamples exactly like one long input.
+#[derive(Clone)]
+pub(crate) struct Resampler {
+    up: usize,
+    down: usize,
+    /// The prototype low-pass filter at the upsampled rate, scaled by `up` to keep the amplitude
+    taps: Vec<f64>,
+    /// The number of taps of each polyphase branch
+    taps_per_phase: usize,
+    /// The last `taps_per_phase - 1` input samples, followed by the samples of the current chunk while resampling
+    history: Vec<Complex<f64>>,
+    /// The position of the next output sample at the upsampled rate, relative to the first sample of the next chunk
+    offset: usize,
+}
+
+impl Resampler {
+    /// Create a resampler from `from_hz` to `to_hz`, with the ratio reduced to lowest terms.
+    /// Both rates must be nonzero. Returns `None` if a term of the reduced ratio exceeds [`MAX_RATIO_TERM`].
+    pub(crate) fn new(from_hz: u64, to_hz: u64) -> Option<Self> {
+        let divisor = gcd(from_hz, to_hz);
+        let up = usize::try_from(to_hz / divisor).ok()?;
+        let down = usize::try_from(from_hz / divisor).ok()?;
+        if up.max(down) > MAX_RATIO_TERM {
+            return None;
+        }
+        let half_len = HALF_LEN_FACTOR.checked_mul(up.max(down))?;
+        // Round the length up to whole polyphase branches, the extra taps are zero
+        let taps_per_phase = (2 * half_len + 1).div_ceil(up);
+        #[allow(clippy::cast_precision_loss)]
+        let taps = (0..taps_per_phase * up)
+            .map(|n| match n {
+                n if n <= 2 * half_len => {
+                    let t = n as f64 - half_len as f64;
+                    let cutoff = 0.5 / up.max(down) as f64;
+                    let window = kaiser(t / half_len as f64);
+                    up as f64 * 2.0 * cutoff * sinc(2.0 * cutoff * t) * window
+                }
+                _ => 0.0,
+            })
+            .collect();
+        Some(Resampler {
+            up,
+            down,
+            taps,
+            taps_per_phase,
+            history: vec![Complex::new(0.0, 0.0); taps_per_phase - 1],
+            offset: 0,
+        })
+    }
+
+    /// Forget the previous input samples, as if the resampler was just created.
+    pub(crate) fn reset(&mut self) {
+        self.history.fill(Complex::new(0.0, 0.0));
+        self.offset = 0;
+    }
+
+    /// Resample a chunk in place. The number of output samples varies between chunks,
+    /// so that the total over all chunks is about `up / down` times the number of input samples.
+    pub(crate) fn process<T: Float + FromPrimitive>(&mut self, samples: &mut Vec<Complex<T>>) {
+        let delay = self.taps_per_phase - 1;
+        self.history.extend(samples.iter().map(|sample| {
+            Complex::new(sample.re.to_f64().unwrap_or(f64::NAN), sample.im.to_f64().unwrap_or(f64::NAN))
+        }));
+        let input_len = samples.len();
+        samples.clear();
+        loop {
+            let (n, phase) = (self.offset / self.up, self.offset % self.up);
+            if n >= input_len {
+                break;
+            }
+            // `history[delay + n]` is input sample n, older samples come before it
+            let output = (0..self.taps_per_phase)
+                .map(|j| self.history[delay + n - j] * self.taps[phase + j * self.up])
+                .sum::<Complex<f64>>();
+            samples.push(Complex::new(
+                T::from_f64(output.re).unwrap_or_else(T::nan),
+                T::from_f64(output.im).unwrap_or_else(T::nan),
+            ));
+            self.offset += self.down;
+        }
+        self.offset -= input_len * self.up;
+        self.history.drain(..input_len);
+    }
+}
+
+/// The Kaiser window at `x` in [-1.0, 1.0].
+fn kaiser(x: f64) -> f64 {
+    bessel_i0(KAISER_BETA * (1.0 - x * x).max(0.0).sqrt()) / bessel_i0(KAISER_BETA)
+}
+
+/// The modified Bessel function of the first kind of order 0, by its power series.
+fn bessel_i0(x: f64) -> f64 {
+    let (mut sum, mut term) = (1.0, 1.0);
+    for k in 1..50 {
+        term *= (x / (2.0 * f64::from(k))).powi(2);
+        sum += term;
+        if term < sum * 1e-16 {
+            break;
+        }
+    }
+    sum
+}
+
+/// The normalized sinc function `sin(πx) / (πx)`.
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) }
+}
+
+/// The greatest common divisor of `a` and `b`.
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunked_matches_whole() {
+        let input = (0..1000u16).map(|n| Complex::from_polar(1.0, f64::from(n) * 0.3)).collect::<Vec<_>>();
+        let mut whole = input.clone();
+        Resampler::new(3, 2).unwrap().process(&mut whole);
+        assert_eq!(whole.len(), 667);
+
+        let mut resampler = Resampler::new(3, 2).unwrap();
+        let mut chunked = Vec::new();
+        for chunk in input.chunks(77) {
+            let mut chunk = chunk.to_vec();
+            resampler.process(&mut chunk);
+            chunked.extend(chunk);
+        }
+        assert_eq!(chunked, whole);
+    }
+}
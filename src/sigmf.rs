@@ -0,0 +1,314 @@
+//! Loading recordings in the [SigMF](https://sigmf.org) format: a `.sigmf-data` file with the raw samples
+//! next to a `.sigmf-meta` JSON file describing them.
+
+use std::path::Path;
+use std::time::SystemTime;
+use bon::bon;
+use serde_json::{json, Value};
+use crate::input::open_file;
+use crate::metadata::{format_iso8601_utc, parse_iso8601_utc};
+use crate::{CaptureMeta, Endianness, InputFile, SampleType, SdrFileReader, SdrReaderError, TrailingPolicy};
+
+/// The contents of a `.sigmf-meta` file that are relevant for reading the samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigmfMeta {
+    /// The `core:datatype` of the recording, e.g. `cf32_le`
+    pub datatype: String,
+    /// The `core:sample_rate` in Hz
+    pub sample_rate_hz: Option<f64>,
+    /// The `core:frequency` of the first capture segment in Hz
+    pub center_freq_hz: Option<f64>,
+    /// The `core:datetime` of the first capture segment
+    pub timestamp: Option<SystemTime>,
+    /// The annotations of the recording
+    pub annotations: Vec<SigmfAnnotation>,
+}
+
+/// An annotation describing a region of the recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigmfAnnotation {
+    /// The `core:sample_start` of the annotated region
+    pub sample_start: u64,
+    /// The `core:sample_count` of the annotated region, `None` if it extends to the end of the recording
+    pub sample_count: Option<u64>,
+    /// The `core:freq_lower_edge` of the annotated region in Hz
+    pub freq_lower_edge_hz: Option<f64>,
+    /// The `core:freq_upper_edge` of the annotated region in Hz
+    pub freq_upper_edge_hz: Option<f64>,
+    /// The `core:label` of the annotation
+    pub label: Option<String>,
+    /// The `core:comment` of the annotation
+    pub comment: Option<String>,
+}
+
+impl SigmfMeta {
+    /// Parse the JSON contents of a `.sigmf-meta` file.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidMetadata` if the JSON is malformed or the `core:datatype` field is missing
+    pub fn parse(json: &str) -> Result<Self, SdrReaderError> {
+        let root: Value = serde_json::from_str(json).map_err(|why| invalid_metadata(&format!("Invalid SigMF metadata: {why}")))?;
+        let global = root.get("global").ok_or_else(|| invalid_metadata("SigMF metadata has no global object"))?;
+        let datatype = global
+            .get("core:datatype")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid_metadata("SigMF metadata has no core:datatype"))?
+            .to_string();
+        let first_capture = root.get("captures").and_then(|captures| captures.get(0));
+        let annotations = root
+            .get("annotations")
+            .and_then(Value::as_array)
+            .map(|annotations| annotations.iter().filter_map(parse_annotation).collect())
+            .unwrap_or_default();
+        Ok(SigmfMeta {
+            datatype,
+            sample_rate_hz: global.get("core:sample_rate").and_then(Value::as_f64),
+            center_freq_hz: first_capture.and_then(|capture| capture.get("core:frequency")).and_then(Value::as_f64),
+            timestamp: first_capture
+                .and_then(|capture| capture.get("core:datetime"))
+                .and_then(Value::as_str)
+                .and_then(parse_iso8601_utc),
+            annotations,
+        })
+    }
+
+    /// The [`SampleType`] and [`Endianness`] described by the `core:datatype`.
+    /// Returns `None` for real-valued or otherwise unsupported datatypes.
+    #[must_use]
+    pub fn sample_format(&self) -> Option<(SampleType, Endianness)> {
+        parse_datatype(&self.datatype)
+    }
+
+    /// The capture metadata, if both the sample rate and the center frequency are known.
+    #[must_use]
+    pub fn capture_meta(&self) -> Option<CaptureMeta> {
+        Some(CaptureMeta {
+            timestamp: self.timestamp,
+            center_freq_hz: round_hz(self.center_freq_hz?),
+            sample_rate_hz: round_hz(self.sample_rate_hz?),
+        })
+    }
+}
+
+/// Round a frequency from the metadata to whole Hz.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn round_hz(hz: f64) -> u64 {
+    hz.round() as u64
+}
+
+fn invalid_metadata(message: &str) -> SdrReaderError {
+    SdrReaderError::InvalidMetadata(message.to_string())
+}
+
+fn parse_annotation(annotation: &Value) -> Option<SigmfAnnotation> {
+    let string = |key| annotation.get(key).and_then(Value::as_str).map(str::to_string);
+    Some(SigmfAnnotation {
+        sample_start: annotation.get("core:sample_start")?.as_u64()?,
+        sample_count: annotation.get("core:sample_count").and_then(Value::as_u64),
+        freq_lower_edge_hz: annotation.get("core:freq_lower_edge").and_then(Value::as_f64),
+        freq_upper_edge_hz: annotation.get("core:freq_upper_edge").and_then(Value::as_f64),
+        label: string("core:label"),
+        comment: string("core:comment"),
+    })
+}
+
+/// Map a complex `SigMF` datatype like `ci16_le` to the matching sample type and byte order.
+fn parse_datatype(datatype: &str) -> Option<(SampleType, Endianness)> {
+    let (format, endianness) = match datatype.split_once('_') {
+        Some((format, "le")) => (format, Endianness::Little),
+        Some((format, "be")) => (format, Endianness::Big),
+        Some(_) => return None,
+        // Single byte types have no byte order suffix
+        None => (datatype, Endianness::Little),
+    };
+    let sample_type = match format {
+        "cu8" => SampleType::U8,
+        "ci8" => SampleType::I8,
+        "cu16" => SampleType::U16,
+        "ci16" => SampleType::I16,
+        "cf32" => SampleType::F32,
+        "cf64" => SampleType::F64,
+        _ => return None,
+    };
+    Some((sample_type, endianness))
+}
+
+/// Map a sample type and byte order to the matching complex `SigMF` datatype like `ci16_le`, the inverse of [`parse_datatype`].
+/// Returns `None` for sample types `SigMF` has no datatype for.
+pub(crate) fn format_datatype(sample_type: SampleType, endianness: &Endianness) -> Option<String> {
+    let format = match sample_type {
+        // Single byte types have no byte order suffix
+        SampleType::U8 => return Some("cu8".to_string()),
+        SampleType::I8 => return Some("ci8".to_string()),
+        SampleType::U16 => "cu16",
+        SampleType::I16 => "ci16",
+        SampleType::F32 => "cf32",
+        SampleType::F64 => "cf64",
+        SampleType::F16 | SampleType::I24 => return None,
+    };
+    let big_endian = match endianness {
+        Endianness::Little => false,
+        Endianness::Big => true,
+        Endianness::Native => cfg!(target_endian = "big"),
+    };
+    Some(format!("{format}_{}", if big_endian { "be" } else { "le" }))
+}
+
+/// The JSON contents of a `.sigmf-meta` file for a recording of `datatype` samples with a single capture segment.
+pub(crate) fn meta_json(datatype: &str, meta: &CaptureMeta) -> String {
+    let mut capture = json!({ "core:sample_start": 0, "core:frequency": meta.center_freq_hz });
+    if let Some(datetime) = meta.timestamp.and_then(format_iso8601_utc) {
+        capture["core:datetime"] = Value::from(datetime);
+    }
+    let root = json!({
+        "global": { "core:datatype": datatype, "core:sample_rate": meta.sample_rate_hz, "core:version": "1.0.0" },
+        "captures": [capture],
+        "annotations": [],
+    });
+    format!("{root:#}")
+}
+
+#[bon]
+impl SdrFileReader {
+    /// Create a new `SdrFileReader` for a `SigMF` recording.
+    /// The metadata is read from `meta_path` and the samples from the `.sigmf-data` file next to it.
+    /// The sample type and byte order are taken from the `core:datatype` of the metadata, unless `sample_type` is set.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use sdr_iq_file_reader::SdrFileReader;
+    /// let mut reader = SdrFileReader::from_sigmf("capture.sigmf-meta", 1024)
+    ///     .build()
+    ///     .expect("Failed to open SigMF recording");
+    /// for annotation in reader.annotations() {
+    ///     println!("{:?} at sample {}", annotation.label, annotation.sample_start);
+    /// }
+    /// let samples = reader.read_next_chunk_complexf32().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidMetadata` if the metadata is malformed, or the datatype is not supported and `sample_type` is not set
+    /// - `SdrReaderError::Open` if the data file could not be opened
+    /// - `SdrReaderError::Io` if the metadata file could not be read
+    #[builder(finish_fn = build)]
+    pub fn from_sigmf(
+        #[builder(start_fn)] meta_path: impl AsRef<Path>,
+        #[builder(start_fn)] samples_per_chunk: usize,
+        /// The type of the samples, instead of the one given by the `core:datatype`, e.g. for datatypes this crate doesn't know.
+        /// The byte order is still taken from the datatype, and is little-endian if the datatype is not supported.
+        sample_type: Option<SampleType>,
+        /// Center unsigned samples around zero, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::center_unsigned).
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0], see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::normalize).
+        #[builder(default)] normalize: bool,
+        /// What happens to the trailing bytes that don't make up a whole chunk, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::trailing_bytes).
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+    ) -> Result<Self, SdrReaderError> {
+        let meta_path = meta_path.as_ref();
+        let sigmf = SigmfMeta::parse(&std::fs::read_to_string(meta_path)?)?;
+        let (sample_type, endianness) = match (sample_type, sigmf.sample_format()) {
+            (Some(sample_type), format) => (sample_type, format.map_or(Endianness::Little, |(_, endianness)| endianness)),
+            (None, Some(format)) => format,
+            (None, None) => return Err(invalid_metadata(&format!("Unsupported SigMF datatype {}", sigmf.datatype))),
+        };
+        let file = open_file(&meta_path.with_extension("sigmf-data"))?;
+        let mut reader = SdrFileReader::from_reader(InputFile::from(file), samples_per_chunk, sample_type)
+            .endianness(endianness)
+            .center_unsigned(center_unsigned)
+            .normalize(normalize)
+            .trailing_bytes(trailing_bytes)
+            .maybe_metadata(sigmf.capture_meta())
+            .maybe_sample_rate_hz(sigmf.sample_rate_hz.map(round_hz))
+            .maybe_center_freq_hz(sigmf.center_freq_hz.map(round_hz))
+            .build()?
+            .with_seek();
+        reader.sigmf = Some(sigmf);
+        Ok(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex;
+
+    const META: &str = r#"{
+        "global": {
+            "core:datatype": "ci16_le",
+            "core:sample_rate": 1000000,
+            "core:version": "1.0.0"
+        },
+        "captures": [
+            { "core:sample_start": 0, "core:frequency": 433920000, "core:datetime": "2024-09-29T01:52:18Z" }
+        ],
+        "annotations": [
+            { "core:sample_start": 1, "core:sample_count": 2, "core:label": "burst" }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_datatype() {
+        assert!(matches!(parse_datatype("cf32_le"), Some((SampleType::F32, Endianness::Little))));
+        assert!(matches!(parse_datatype("cf64_be"), Some((SampleType::F64, Endianness::Big))));
+        assert!(matches!(parse_datatype("ci16_le"), Some((SampleType::I16, Endianness::Little))));
+        assert!(matches!(parse_datatype("cu8"), Some((SampleType::U8, Endianness::Little))));
+        assert!(parse_datatype("rf32_le").is_none());
+        assert!(parse_datatype("ci32_le").is_none());
+        assert!(parse_datatype("cf32_xx").is_none());
+    }
+
+    #[test]
+    fn test_format_datatype() {
+        assert_eq!(format_datatype(SampleType::I16, &Endianness::Little).as_deref(), Some("ci16_le"));
+        assert_eq!(format_datatype(SampleType::F64, &Endianness::Big).as_deref(), Some("cf64_be"));
+        assert_eq!(format_datatype(SampleType::U8, &Endianness::Big).as_deref(), Some("cu8"));
+        assert_eq!(format_datatype(SampleType::I24, &Endianness::Little), None);
+        for sample_type in [SampleType::U8, SampleType::I8, SampleType::U16, SampleType::I16, SampleType::F32, SampleType::F64] {
+            let datatype = format_datatype(sample_type, &Endianness::Big).unwrap();
+            assert!(matches!(parse_datatype(&datatype), Some((parsed, _)) if parsed == sample_type));
+        }
+    }
+
+    #[test]
+    fn test_from_sigmf() {
+        let dir = std::env::temp_dir();
+        let meta_path = dir.join("sdr_iq_file_reader_from_sigmf.sigmf-meta");
+        std::fs::write(&meta_path, META).unwrap();
+        let mut data = Vec::new();
+        for value in [1i16, -1, 2, -2, 3, -3] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        std::fs::write(dir.join("sdr_iq_file_reader_from_sigmf.sigmf-data"), data).unwrap();
+
+        let mut reader = SdrFileReader::from_sigmf(&meta_path, 3)
+            .build()
+            .expect("Failed to open SigMF recording");
+        assert_eq!(reader.sample_type(), SampleType::I16);
+        let metadata = reader.metadata().expect("SigMF recording should have metadata");
+        assert_eq!(metadata.sample_rate_hz, 1_000_000);
+        assert_eq!(metadata.center_freq_hz, 433_920_000);
+        assert!(metadata.timestamp.is_some());
+        assert_eq!(reader.sample_rate_hz(), Some(1_000_000));
+        assert_eq!(reader.center_freq_hz(), Some(433_920_000));
+        assert_eq!(reader.annotations().len(), 1);
+        assert_eq!(reader.annotations()[0].sample_start, 1);
+        assert_eq!(reader.annotations()[0].sample_count, Some(2));
+        assert_eq!(reader.annotations()[0].label.as_deref(), Some("burst"));
+        assert_eq!(
+            reader.read_next_chunk_complexf32().unwrap().unwrap(),
+            vec![Complex::new(1.0, -1.0), Complex::new(2.0, -2.0), Complex::new(3.0, -3.0)]
+        );
+    }
+
+    #[test]
+    fn test_from_sigmf_unsupported_datatype() {
+        let meta_path = std::env::temp_dir().join("sdr_iq_file_reader_unsupported.sigmf-meta");
+        std::fs::write(&meta_path, META.replace("ci16_le", "ri16_le")).unwrap();
+        let error = SdrFileReader::from_sigmf(&meta_path, 3).build().err().expect("Real datatypes should be rejected");
+        assert!(matches!(error, SdrReaderError::InvalidMetadata(_)));
+        // Unless the sample type is set explicitly
+        std::fs::write(meta_path.with_extension("sigmf-data"), [0u8; 8]).unwrap();
+        let reader = SdrFileReader::from_sigmf(&meta_path, 3).sample_type(SampleType::U16).build().unwrap();
+        assert_eq!(reader.sample_type(), SampleType::U16);
+    }
+}
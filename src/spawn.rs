@@ -0,0 +1,80 @@
+//! Reading chunks on a background thread.
+
+use std::io::Read;
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::thread::JoinHandle;
+use num_complex::Complex;
+use crate::{SdrFileReader, SdrReaderError};
+
+/// The number of chunks the background thread reads ahead of the receiver.
+const CHANNEL_CAPACITY: usize = 4;
+
+impl<R: Read + Send + 'static> SdrFileReader<R> {
+    /// Move the reader to a background thread that reads the chunks as Complex<f32> and sends them to the returned channel,
+    /// e.g. to keep the I/O out of the render loop of a GUI.
+    /// The thread reads at most 4 chunks ahead, then waits for the receiver to catch up.
+    /// It stops at the end of the file, after sending an error, or when the receiver is dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(1024)
+    ///     .sample_type(SampleType::F32)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// let (handle, receiver) = reader.spawn_reader();
+    /// for chunk in receiver {
+    ///     let samples = chunk.unwrap();
+    /// }
+    /// handle.join().unwrap();
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn spawn_reader(mut self) -> (JoinHandle<()>, Receiver<Result<Vec<Complex<f32>>, SdrReaderError>>) {
+        let (sender, receiver) = sync_channel(CHANNEL_CAPACITY);
+        let handle = std::thread::spawn(move || loop {
+            let chunk = match self.read_next_chunk_complexf32() {
+                Ok(Some(samples)) => Ok(samples),
+                Ok(None) => break,
+                Err(why) => Err(why),
+            };
+            let failed = chunk.is_err();
+            if sender.send(chunk).is_err() || failed {
+                break;
+            }
+        });
+        (handle, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SampleType, TrailingPolicy};
+
+    #[test]
+    fn test_spawn_reader() {
+        let reader = SdrFileReader::builder()
+            .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+            .samples_per_chunk(1024)
+            .sample_type(SampleType::F32)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let (handle, receiver) = reader.spawn_reader();
+        let chunks = receiver.iter().collect::<Result<Vec<_>, _>>().unwrap();
+        handle.join().unwrap();
+        assert_eq!(chunks.len(), 16);
+        assert!(chunks.iter().all(|chunk| chunk.len() == 1024));
+    }
+
+    #[test]
+    fn test_spawn_reader_stops_after_error() {
+        let reader = SdrFileReader::from_bytes(vec![0; 9], 1, SampleType::I16).trailing_bytes(TrailingPolicy::PartialChunk).build().unwrap();
+        let (handle, receiver) = reader.spawn_reader();
+        let chunks = receiver.iter().collect::<Vec<_>>();
+        handle.join().unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert!(matches!(chunks[2], Err(SdrReaderError::UnexpectedPartialSample { bytes_left: 1 })));
+    }
+}
@@ -0,0 +1,201 @@
+//! Frequency analysis of chunks with rustfft.
+
+use std::f64::consts::TAU;
+use std::io::Read;
+use num_complex::Complex;
+use rustfft::FftPlanner;
+use crate::{SdrFileReader, SdrReaderError};
+
+/// The power of an empty bin in [`SdrFileReader::spectrogram`], so silent bins are -200 dB instead of minus infinity.
+const POWER_FLOOR: f32 = 1e-20;
+
+/// The window function applied to each frame of a spectrogram to reduce spectral leakage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowFunction {
+    /// The Hann (raised cosine) window, a good default for most signals
+    #[default]
+    Hann,
+    /// The Hamming window, with lower first side lobes than Hann but slower falling ones
+    Hamming,
+    /// The Blackman window, with very low side lobes at the cost of a wider main lobe
+    Blackman,
+    /// No window, for the best frequency resolution with the most leakage
+    Rectangular,
+}
+
+impl WindowFunction {
+    /// The `len` coefficients of the (symmetric) window.
+    fn coefficients(self, len: usize) -> Vec<f32> {
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        (0..len)
+            .map(|n| {
+                let x = if len > 1 { TAU * n as f64 / (len - 1) as f64 } else { 0.0 };
+                let coefficient = match self {
+                    WindowFunction::Hann => 0.5 - 0.5 * x.cos(),
+                    WindowFunction::Hamming => 0.54 - 0.46 * x.cos(),
+                    WindowFunction::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                    WindowFunction::Rectangular => 1.0,
+                };
+                coefficient as f32
+            })
+            .collect()
+    }
+}
+
+/// The frequency in Hz of the strongest bin of the FFT of a chunk, e.g. to find the dominant signal in it.
+/// Bins in the upper half of the FFT are negative frequencies, so the result is between `-sample_rate_hz / 2`
+/// and `sample_rate_hz / 2`, with a resolution of `sample_rate_hz / chunk.len()`.
+/// An empty chunk has a peak at 0.0.
+///
+/// # Example
+/// ```
+/// use num_complex::Complex;
+/// use sdr_iq_file_reader::chunk_peak_frequency;
+/// // A tone at a quarter of the sample rate
+/// let chunk = [Complex::new(1.0, 0.0), Complex::new(0.0, 1.0), Complex::new(-1.0, 0.0), Complex::new(0.0, -1.0)];
+/// assert_eq!(chunk_peak_frequency(&chunk, 1000.0), 250.0);
+/// ```
+#[must_use]
+pub fn chunk_peak_frequency(chunk: &[Complex<f32>], sample_rate_hz: f64) -> f32 {
+    if chunk.is_empty() {
+        return 0.0;
+    }
+    let mut spectrum = chunk.to_vec();
+    FftPlanner::new().plan_fft_forward(spectrum.len()).process(&mut spectrum);
+    let (peak, _) = spectrum
+        .iter()
+        .map(Complex::norm_sqr)
+        .enumerate()
+        .fold((0, f32::NEG_INFINITY), |(peak, max), (bin, power)| if power > max { (bin, power) } else { (peak, max) });
+    let len = spectrum.len();
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let frequency = {
+        let bin = if peak < len.div_ceil(2) { peak as f64 } else { peak as f64 - len as f64 };
+        (bin * sample_rate_hz / len as f64) as f32
+    };
+    frequency
+}
+
+impl<R: Read> SdrFileReader<R> {
+    /// Read all remaining samples and compute their spectrogram with a Hann window, see [`SdrFileReader::spectrogram_with_window`].
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `fft_size` is 0 or `overlap` is not smaller than `fft_size`
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`](crate::TrailingPolicy)
+    pub fn spectrogram(&mut self, fft_size: usize, overlap: usize) -> Result<Vec<Vec<f32>>, SdrReaderError> {
+        self.spectrogram_with_window(fft_size, overlap, WindowFunction::default())
+    }
+
+    /// Read all remaining samples and compute their spectrogram, one row per frame of `fft_size` samples,
+    /// with successive frames sharing `overlap` samples. Each frame is multiplied by the `window`,
+    /// transformed with an FFT and shifted so that DC is in the middle column `fft_size / 2`,
+    /// with the negative frequencies to the left of it, ready for display.
+    ///
+    /// The values are the power of each bin in dB, scaled so that a tone with an amplitude of 1.0 at the center of a bin
+    /// reads 0 dB, and floored at -200 dB. Samples at the end that don't fill a whole frame are dropped.
+    /// The frames are independent of `samples_per_chunk`, and all processing options of the reader are applied first.
+    ///
+    /// # Example
+    /// ```
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType, WindowFunction};
+    /// let mut reader = SdrFileReader::builder()
+    ///     .file_path("gqrx_20240929_015218_580206500_2400000_fc.raw")
+    ///     .samples_per_chunk(4096)
+    ///     .sample_type(SampleType::F32)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileReader");
+    /// let rows = reader.spectrogram_with_window(1024, 512, WindowFunction::Blackman).unwrap();
+    /// assert_eq!(rows[0].len(), 1024);
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `fft_size` is 0 or `overlap` is not smaller than `fft_size`
+    /// - `SdrReaderError::Io` if there was an error reading the file other than reaching the end
+    /// - `SdrReaderError::UnexpectedPartialSample` or `SdrReaderError::UnexpectedPartialChunk` if the file doesn't end on a chunk boundary,
+    ///   depending on the [`TrailingPolicy`](crate::TrailingPolicy)
+    pub fn spectrogram_with_window(&mut self, fft_size: usize, overlap: usize, window: WindowFunction) -> Result<Vec<Vec<f32>>, SdrReaderError> {
+        if fft_size == 0 || overlap >= fft_size {
+            return Err(SdrReaderError::InvalidConfig(format!(
+                "The spectrogram needs a nonzero fft_size and an overlap smaller than it, not {fft_size} and {overlap}"
+            )));
+        }
+        let coefficients = window.coefficients(fft_size);
+        let window_sum = coefficients.iter().sum::<f32>();
+        let fft = FftPlanner::new().plan_fft_forward(fft_size);
+        let mut rows = Vec::new();
+        let mut pending = Vec::with_capacity(fft_size + self.samples_per_chunk);
+        let mut chunk = Vec::with_capacity(self.samples_per_chunk);
+        let mut frame = vec![Complex::default(); fft_size];
+        while self.read_next_chunk_into(&mut chunk)?.is_some() {
+            pending.extend_from_slice(&chunk);
+            while pending.len() >= fft_size {
+                for ((bin, sample), coefficient) in frame.iter_mut().zip(&pending).zip(&coefficients) {
+                    *bin = sample * coefficient;
+                }
+                fft.process(&mut frame);
+                frame.rotate_right(fft_size / 2);
+                let row = frame
+                    .iter()
+                    .map(|bin| 10.0 * (bin.norm_sqr() / (window_sum * window_sum)).max(POWER_FLOOR).log10())
+                    .collect();
+                rows.push(row);
+                pending.drain(..fft_size - overlap);
+            }
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::SampleType;
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_chunk_peak_frequency() {
+        let bin_width = 48_000.0 / 1024.0;
+        for frequency in [12_345.0, -5000.0, 0.0] {
+            let chunk = (0..1024u16)
+                .map(|n| {
+                    let sample = Complex::from_polar(1.0, TAU * frequency * f64::from(n) / 48_000.0);
+                    Complex::new(sample.re as f32, sample.im as f32)
+                })
+                .collect::<Vec<_>>();
+            let peak = f64::from(chunk_peak_frequency(&chunk, 48_000.0));
+            assert!((peak - frequency).abs() <= bin_width, "Peak at {peak} Hz instead of {frequency} Hz");
+        }
+        assert!(chunk_peak_frequency(&[], 48_000.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_spectrogram() {
+        // A tone in bin 16 of a 256-point FFT, read in chunks that don't line up with the frames
+        let bytes = (0..5000u16)
+            .flat_map(|n| {
+                let sample = Complex::from_polar(0.5f32, std::f32::consts::TAU * 16.0 * f32::from(n) / 256.0);
+                [sample.re.to_le_bytes(), sample.im.to_le_bytes()].concat()
+            })
+            .collect::<Vec<_>>();
+        let build = || SdrFileReader::from_reader(Cursor::new(bytes.clone()), 300, SampleType::F32).build().unwrap();
+        let rows = build().spectrogram(256, 128).unwrap();
+        // The final chunk of 200 samples is dropped by the reader, which leaves 4800 samples for the frames
+        assert_eq!(rows.len(), (4800 - 256) / 128 + 1);
+        for row in &rows {
+            assert_eq!(row.len(), 256);
+            let (peak, level) = row.iter().enumerate().fold((0, f32::NEG_INFINITY), |max, (bin, &level)| if level > max.1 { (bin, level) } else { max });
+            assert_eq!(peak, 128 + 16);
+            // An amplitude of 0.5 is -6 dB
+            assert!((level + 6.02).abs() < 0.1, "{level}");
+            assert!(row.iter().enumerate().filter(|&(bin, _)| bin.abs_diff(peak) > 2).all(|(_, &level)| level < -60.0));
+        }
+
+        let rows = build().spectrogram_with_window(256, 0, WindowFunction::Rectangular).unwrap();
+        assert_eq!(rows.len(), 4800 / 256);
+        assert!(matches!(build().spectrogram(256, 256), Err(SdrReaderError::InvalidConfig(_))));
+        assert!(matches!(build().spectrogram(0, 0), Err(SdrReaderError::InvalidConfig(_))));
+    }
+}
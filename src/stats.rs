@@ -0,0 +1,119 @@
+//! Summary statistics over a chunk of samples.
+
+use num_complex::Complex;
+
+/// Level statistics over a chunk of samples, computed by [`chunk_stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkStats {
+    /// The largest magnitude `|x|` of any sample
+    pub max_magnitude: f32,
+    /// The mean of the power `|x|²` over all samples
+    pub mean_power: f32,
+    /// The root mean square of the magnitude, the square root of `mean_power`
+    pub rms: f32,
+    /// The number of samples with a component at or above full scale (`|i| >= 1.0` or `|q| >= 1.0`)
+    pub clipped_count: usize,
+}
+
+/// Compute the level statistics of a chunk of normalized samples, e.g. for automatic gain control or clipping detection.
+/// Full scale is 1.0, as returned by the reader with `normalize` (and `center_unsigned` for unsigned types) enabled.
+/// An empty chunk has all statistics at zero.
+///
+/// # Example
+/// ```
+/// use num_complex::Complex;
+/// use sdr_iq_file_reader::chunk_stats;
+/// let stats = chunk_stats(&[Complex::new(0.6, 0.8), Complex::new(1.0, 0.0)]);
+/// assert_eq!(stats.clipped_count, 1);
+/// ```
+#[must_use]
+pub fn chunk_stats(chunk: &[Complex<f32>]) -> ChunkStats {
+    let mut max_power = 0.0f32;
+    let mut total_power = 0.0f64;
+    let mut clipped_count = 0;
+    for sample in chunk {
+        let power = sample.norm_sqr();
+        max_power = max_power.max(power);
+        total_power += f64::from(power);
+        if sample.re.abs() >= 1.0 || sample.im.abs() >= 1.0 {
+            clipped_count += 1;
+        }
+    }
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    let mean_power = if chunk.is_empty() { 0.0 } else { (total_power / chunk.len() as f64) as f32 };
+    ChunkStats {
+        max_magnitude: max_power.sqrt(),
+        mean_power,
+        rms: mean_power.sqrt(),
+        clipped_count,
+    }
+}
+
+/// Whether a chunk of samples is silent, i.e. its mean power `|x|²` is below `threshold`, e.g. for a squelch.
+/// An empty chunk is silent for any positive threshold.
+///
+/// # Example
+/// ```
+/// use num_complex::Complex;
+/// use sdr_iq_file_reader::chunk_is_silent;
+/// assert!(chunk_is_silent(&[Complex::new(0.001, 0.0); 16], 1e-4));
+/// assert!(!chunk_is_silent(&[Complex::new(0.1, 0.0); 16], 1e-4));
+/// ```
+#[must_use]
+pub fn chunk_is_silent(chunk: &[Complex<f32>], threshold: f32) -> bool {
+    chunk_stats(chunk).mean_power < threshold
+}
+
+/// The number of samples in a chunk whose magnitude `|x|` is above `threshold`, e.g. to estimate the duty cycle of a signal.
+///
+/// # Example
+/// ```
+/// use num_complex::Complex;
+/// use sdr_iq_file_reader::count_above_threshold;
+/// let chunk = [Complex::new(0.5, 0.0), Complex::new(0.0, 0.05), Complex::new(-0.3, 0.4)];
+/// assert_eq!(count_above_threshold(&chunk, 0.1), 2);
+/// ```
+#[must_use]
+pub fn count_above_threshold(chunk: &[Complex<f32>], threshold: f32) -> usize {
+    let threshold_power = threshold * threshold.abs();
+    chunk.iter().filter(|sample| sample.norm_sqr() > threshold_power).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_stats() {
+        let chunk = [Complex::new(0.0, 0.0), Complex::new(0.3, -0.4), Complex::new(-1.0, 0.0), Complex::new(0.0, 0.0)];
+        let stats = chunk_stats(&chunk);
+        assert!((stats.max_magnitude - 1.0).abs() < 1e-6);
+        assert!((stats.mean_power - 1.25 / 4.0).abs() < 1e-6);
+        assert!((stats.rms - (1.25f32 / 4.0).sqrt()).abs() < 1e-6);
+        assert_eq!(stats.clipped_count, 1);
+    }
+
+    #[test]
+    fn test_chunk_is_silent() {
+        let silent = [Complex::new(0.001, -0.002); 64];
+        let active = (0..64u8).map(|n| Complex::from_polar(0.5, f32::from(n) * 0.1)).collect::<Vec<_>>();
+        assert!(chunk_is_silent(&silent, 1e-4));
+        assert!(!chunk_is_silent(&active, 1e-4));
+        assert!(chunk_is_silent(&active, 1.0));
+    }
+
+    #[test]
+    fn test_count_above_threshold() {
+        let chunk = (0..100u8).map(|n| Complex::from_polar(f32::from(n) / 100.0, f32::from(n))).collect::<Vec<_>>();
+        assert_eq!(count_above_threshold(&chunk, 0.895), 10);
+        assert_eq!(count_above_threshold(&chunk, 0.0), 99);
+        assert_eq!(count_above_threshold(&chunk, -1.0), 100);
+        assert_eq!(count_above_threshold(&[], 0.5), 0);
+    }
+
+    #[test]
+    fn test_chunk_stats_empty() {
+        let stats = chunk_stats(&[]);
+        assert_eq!(stats, ChunkStats { max_magnitude: 0.0, mean_power: 0.0, rms: 0.0, clipped_count: 0 });
+    }
+}
@@ -0,0 +1,75 @@
+//! Reading the decoded samples as a stream of bytes.
+
+use std::io::Read;
+use crate::SdrFileReader;
+
+/// A [`Read`] adapter that yields the samples of a reader as interleaved little-endian f32 bytes.
+struct F32ByteReader<R> {
+    reader: SdrFileReader<R>,
+    samples: Vec<f32>,
+    /// The bytes of the last chunk
+    bytes: Vec<u8>,
+    /// The number of bytes of `bytes` already returned
+    consumed: usize,
+}
+
+impl<R: Read> Read for F32ByteReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Chunks can be empty after decimation or resampling, so read until one has samples
+        while self.consumed == self.bytes.len() {
+            if self.reader.read_next_chunk_interleaved_f32(&mut self.samples)?.is_none() {
+                return Ok(0);
+            }
+            self.bytes.clear();
+            self.bytes.extend(self.samples.iter().flat_map(|value| value.to_le_bytes()));
+            self.consumed = 0;
+        }
+        let len = buf.len().min(self.bytes.len() - self.consumed);
+        buf[..len].copy_from_slice(&self.bytes[self.consumed..self.consumed + len]);
+        self.consumed += len;
+        Ok(len)
+    }
+}
+
+impl<R: Read> SdrFileReader<R> {
+    /// Turn the reader into a [`Read`] stream of the decoded samples as interleaved little-endian f32 bytes
+    /// (`I0 Q0 I1 Q1 ...`), with all options like `normalize` and `center_unsigned` applied.
+    /// This converts a capture into the `cf32_le` format, e.g. with [`std::io::copy`] into a file or socket.
+    ///
+    /// Errors of the reader are returned as [`std::io::Error`]s, see the conversion from [`SdrReaderError`](crate::SdrReaderError).
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Read;
+    /// use sdr_iq_file_reader::{SdrFileReader, SampleType};
+    /// let reader = SdrFileReader::from_bytes(vec![0, 64, 0, 192], 1, SampleType::I16).normalize(true).build().unwrap();
+    /// let mut bytes = Vec::new();
+    /// reader.into_f32_byte_reader().read_to_end(&mut bytes).unwrap();
+    /// assert_eq!(bytes, [0.5f32.to_le_bytes(), (-0.5f32).to_le_bytes()].concat());
+    /// ```
+    pub fn into_f32_byte_reader(self) -> impl Read {
+        F32ByteReader { reader: self, samples: Vec::new(), bytes: Vec::new(), consumed: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SampleType, TrailingPolicy};
+
+    #[test]
+    fn test_i16_to_f32_bytes() {
+        let values = (-500..500).map(|n| n * 60).collect::<Vec<i16>>();
+        let bytes = values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<_>>();
+        let reader = SdrFileReader::from_bytes(bytes, 64, SampleType::I16)
+            .normalize(true)
+            .trailing_bytes(TrailingPolicy::PartialChunk)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let mut output = Vec::new();
+        std::io::copy(&mut reader.into_f32_byte_reader(), &mut output).unwrap();
+        let decoded = output.chunks_exact(4).map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap())).collect::<Vec<_>>();
+        let expected = values.iter().map(|&value| f32::from(value) / 32768.0).collect::<Vec<_>>();
+        assert_eq!(decoded, expected);
+    }
+}
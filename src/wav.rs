@@ -0,0 +1,364 @@
+//! Reading IQ recordings stored as two-channel WAV files, as written by `SDR#`, `SDRuno`, `HDSDR` and others.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Take};
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use bon::bon;
+use crate::input::open_file;
+use crate::{Endianness, SampleType, SdrFileReader, SdrReaderError, TrailingPolicy};
+
+/// `WAVE_FORMAT_PCM`
+const FORMAT_PCM: u16 = 0x0001;
+/// `WAVE_FORMAT_IEEE_FLOAT`
+const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+/// `WAVE_FORMAT_EXTENSIBLE`, the actual format is stored in the sub-format GUID.
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// The largest `fmt ` or `auxi` chunk that is read into memory. Both are a few hundred bytes at most,
+/// so a larger size comes from a corrupt or hostile header.
+const MAX_HEADER_CHUNK_LEN: u32 = 64 * 1024;
+
+/// The information parsed from the header of a WAV IQ recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WavHeader {
+    /// The WAV format tag (1 for integer PCM, 3 for IEEE float)
+    pub format_tag: u16,
+    /// The number of channels, always 2 (I and Q) for a valid IQ recording
+    pub channels: u16,
+    /// The sample rate in Hz
+    pub sample_rate_hz: u32,
+    /// The number of bits of one component (I or Q)
+    pub bits_per_sample: u16,
+    /// The length of the `data` chunk in bytes
+    pub data_len: u64,
+    /// The center frequency in Hz, if the file contains an SDR# `auxi` chunk
+    pub center_freq_hz: Option<u64>,
+}
+
+impl WavHeader {
+    /// The [`SampleType`] matching the format of the samples in the `data` chunk.
+    /// Returns `None` if the format is not supported.
+    #[must_use]
+    pub fn sample_type(&self) -> Option<SampleType> {
+        match (self.format_tag, self.bits_per_sample) {
+            // 8-bit WAV samples are unsigned, all wider integer formats are signed
+            (FORMAT_PCM, 8) => Some(SampleType::U8),
+            (FORMAT_PCM, 16) => Some(SampleType::I16),
+            (FORMAT_PCM, 24) => Some(SampleType::I24),
+            (FORMAT_IEEE_FLOAT, 32) => Some(SampleType::F32),
+            (FORMAT_IEEE_FLOAT, 64) => Some(SampleType::F64),
+            _ => None,
+        }
+    }
+
+    /// Parse the RIFF/WAVE header from `reader`.
+    /// Afterwards, `reader` is positioned at the first byte of the `data` chunk.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidMetadata` if the header is malformed, the file is not a two-channel recording
+    ///   or the sample format is not supported
+    /// - `SdrReaderError::Io` if there was an error reading the header
+    pub fn parse(reader: &mut impl Read) -> Result<Self, SdrReaderError> {
+        let mut riff = [0u8; 12];
+        reader.read_exact(&mut riff)?;
+        if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+            return Err(invalid_metadata("Not a RIFF/WAVE file"));
+        }
+
+        let mut format = None;
+        let mut center_freq_hz = None;
+        loop {
+            let mut chunk_header = [0u8; 8];
+            match reader.read_exact(&mut chunk_header) {
+                Err(why) if why.kind() == ErrorKind::UnexpectedEof => return Err(invalid_metadata("WAV file has no data chunk")),
+                result => result?,
+            }
+            let id = [chunk_header[0], chunk_header[1], chunk_header[2], chunk_header[3]];
+            let len = u32::from_le_bytes([chunk_header[4], chunk_header[5], chunk_header[6], chunk_header[7]]);
+            match &id {
+                b"fmt " => format = Some(parse_fmt_chunk(&read_chunk(reader, len)?)?),
+                b"auxi" => center_freq_hz = parse_auxi_chunk(&read_chunk(reader, len)?),
+                b"data" => {
+                    let (format_tag, channels, sample_rate_hz, bits_per_sample) =
+                        format.ok_or_else(|| invalid_metadata("WAV data chunk appears before the fmt chunk"))?;
+                    let header = WavHeader {
+                        format_tag,
+                        channels,
+                        sample_rate_hz,
+                        bits_per_sample,
+                        data_len: u64::from(len),
+                        center_freq_hz,
+                    };
+                    if header.channels != 2 {
+                        return Err(invalid_metadata(&format!("Expected 2 channels (I and Q), found {}", header.channels)));
+                    }
+                    if header.sample_type().is_none() {
+                        return Err(invalid_metadata(&format!(
+                            "Unsupported WAV sample format {:#06x} with {} bits per sample",
+                            header.format_tag, header.bits_per_sample
+                        )));
+                    }
+                    return Ok(header);
+                }
+                _ => skip_chunk(reader, len)?,
+            }
+        }
+    }
+}
+
+fn invalid_metadata(message: &str) -> SdrReaderError {
+    SdrReaderError::InvalidMetadata(message.to_string())
+}
+
+/// Read the body of a chunk, including the padding byte of odd-sized chunks.
+/// The length comes from the file, so it is checked against [`MAX_HEADER_CHUNK_LEN`] before allocating.
+fn read_chunk(reader: &mut impl Read, len: u32) -> Result<Vec<u8>, SdrReaderError> {
+    if len > MAX_HEADER_CHUNK_LEN {
+        return Err(invalid_metadata(&format!("WAV header chunk of {len} bytes is too large")));
+    }
+    let mut body = vec![0u8; len as usize + (len as usize % 2)];
+    reader.read_exact(&mut body)?;
+    body.truncate(len as usize);
+    Ok(body)
+}
+
+/// Read past the body of a chunk, including the padding byte of odd-sized chunks, without buffering it.
+fn skip_chunk(reader: &mut impl Read, len: u32) -> Result<(), std::io::Error> {
+    let padded_len = u64::from(len) + u64::from(len % 2);
+    if std::io::copy(&mut reader.by_ref().take(padded_len), &mut std::io::sink())? < padded_len {
+        return Err(std::io::Error::new(ErrorKind::UnexpectedEof, "WAV chunk is truncated"));
+    }
+    Ok(())
+}
+
+/// Returns the format tag, channel count, sample rate and bits per sample.
+fn parse_fmt_chunk(body: &[u8]) -> Result<(u16, u16, u32, u16), SdrReaderError> {
+    if body.len() < 16 {
+        return Err(invalid_metadata("WAV fmt chunk is too short"));
+    }
+    let mut format_tag = u16::from_le_bytes([body[0], body[1]]);
+    let channels = u16::from_le_bytes([body[2], body[3]]);
+    let sample_rate_hz = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+    let bits_per_sample = u16::from_le_bytes([body[14], body[15]]);
+    if format_tag == FORMAT_EXTENSIBLE {
+        // The first two bytes of the sub-format GUID hold the actual format tag
+        if body.len() < 26 {
+            return Err(invalid_metadata("WAV extensible fmt chunk is too short"));
+        }
+        format_tag = u16::from_le_bytes([body[24], body[25]]);
+    }
+    Ok((format_tag, channels, sample_rate_hz, bits_per_sample))
+}
+
+/// The SDR# `auxi` chunk starts with two `SYSTEMTIME` structs (start and stop time), followed by the center frequency.
+fn parse_auxi_chunk(body: &[u8]) -> Option<u64> {
+    let bytes = body.get(32..36)?;
+    Some(u64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])))
+}
+
+/// Reads the IQ samples of a two-channel WAV file.
+/// The sample format is taken from the WAV header, and the left and right channels are decoded as I and Q.
+///
+/// All read methods of [`SdrFileReader`] are available through `Deref`.
+///
+/// # Example
+/// ```no_run
+/// use sdr_iq_file_reader::SdrWavReader;
+/// let mut reader = SdrWavReader::builder()
+///     .file_path("SDRSharp_20241001_120000Z_100000000Hz_IQ.wav")
+///     .samples_per_chunk(1024)
+///     .normalize(true)
+///     .build()
+///     .expect("Failed to create SdrWavReader");
+/// println!("Sample rate: {} Hz", reader.sample_rate_hz());
+/// let samples = reader.read_next_chunk_complexf32().unwrap();
+/// ```
+pub struct SdrWavReader {
+    reader: SdrFileReader<Take<File>>,
+    header: WavHeader,
+}
+
+#[bon]
+impl SdrWavReader {
+    #[allow(missing_docs)]
+    #[builder]
+    pub fn new(
+        file_path: impl AsRef<Path>,
+        samples_per_chunk: usize,
+        /// Subtract the midpoint from 8-bit samples, which are unsigned in WAV files, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::center_unsigned).
+        #[builder(default)] center_unsigned: bool,
+        /// Scale integer samples to the range [-1.0, 1.0], see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::normalize).
+        #[builder(default)] normalize: bool,
+        /// What happens to the trailing bytes that don't make up a whole chunk, see [`SdrFileReader::from_reader`](crate::SdrFileReaderFromReaderBuilder::trailing_bytes).
+        #[builder(default)] trailing_bytes: TrailingPolicy,
+    ) -> Result<Self, SdrReaderError> {
+        let mut file = open_file(file_path.as_ref())?;
+        let header = WavHeader::parse(&mut file)?;
+        let sample_type = header.sample_type().ok_or_else(|| invalid_metadata("Unsupported WAV sample format"))?;
+        // Limit reading to the data chunk so trailing chunks are not decoded as samples
+        let reader = SdrFileReader::from_reader(file.take(header.data_len), samples_per_chunk, sample_type)
+            .endianness(Endianness::Little)
+            .center_unsigned(center_unsigned)
+            .normalize(normalize)
+            .trailing_bytes(trailing_bytes)
+            .build()?;
+        Ok(SdrWavReader { reader, header })
+    }
+}
+
+impl SdrWavReader {
+    /// The parsed WAV header.
+    #[must_use]
+    pub fn header(&self) -> &WavHeader {
+        &self.header
+    }
+
+    /// The sample rate from the WAV header in Hz.
+    #[must_use]
+    pub fn sample_rate_hz(&self) -> u32 {
+        self.header.sample_rate_hz
+    }
+
+    /// The center frequency in Hz, if the recording contains an SDR# `auxi` chunk.
+    #[must_use]
+    pub fn center_freq_hz(&self) -> Option<u64> {
+        self.header.center_freq_hz
+    }
+
+    /// The total number of samples in the `data` chunk.
+    #[must_use]
+    pub fn total_samples(&self) -> u64 {
+        self.header.sample_type().map_or(0, |sample_type| self.header.data_len / sample_type.sample_len() as u64)
+    }
+}
+
+impl Deref for SdrWavReader {
+    type Target = SdrFileReader<Take<File>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.reader
+    }
+}
+
+impl DerefMut for SdrWavReader {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex;
+
+    /// Build a WAV file with the given format and chunks placed between the fmt and data chunks.
+    fn build_wav(format_tag: u16, bits_per_sample: u16, extra_chunks: &[u8], data: &[u8]) -> Vec<u8> {
+        let block_align = 2 * bits_per_sample / 8;
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&format_tag.to_le_bytes());
+        fmt.extend_from_slice(&2u16.to_le_bytes());
+        fmt.extend_from_slice(&48_000u32.to_le_bytes());
+        fmt.extend_from_slice(&(48_000 * u32::from(block_align)).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits_per_sample.to_le_bytes());
+
+        let mut body = b"WAVE".to_vec();
+        body.extend_from_slice(b"fmt ");
+        body.extend_from_slice(&u32::try_from(fmt.len()).unwrap().to_le_bytes());
+        body.extend_from_slice(&fmt);
+        body.extend_from_slice(extra_chunks);
+        body.extend_from_slice(b"data");
+        body.extend_from_slice(&u32::try_from(data.len()).unwrap().to_le_bytes());
+        body.extend_from_slice(data);
+        // A trailing chunk that must not be decoded as samples
+        body.extend_from_slice(b"LIST\x04\x00\x00\x00INFO");
+
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&u32::try_from(body.len()).unwrap().to_le_bytes());
+        wav.extend_from_slice(&body);
+        wav
+    }
+
+    fn write_temp_wav(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("sdr_iq_file_reader_{name}.wav"));
+        std::fs::write(&path, bytes).expect("Failed to write temp file");
+        path
+    }
+
+    #[test]
+    fn test_read_i16_wav() {
+        let mut data = Vec::new();
+        for value in [100i16, -200, i16::MIN, 32767] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        let path = write_temp_wav("i16", &build_wav(FORMAT_PCM, 16, &[], &data));
+        let mut reader = SdrWavReader::builder()
+            .file_path(&path)
+            .samples_per_chunk(2)
+            .build()
+            .expect("Failed to create SdrWavReader");
+        assert_eq!(reader.sample_rate_hz(), 48_000);
+        assert_eq!(reader.center_freq_hz(), None);
+        assert!(matches!(reader.header().sample_type(), Some(SampleType::I16)));
+        assert_eq!(reader.total_samples(), 2);
+        assert_eq!(
+            reader.read_next_chunk_complexf32().unwrap().unwrap(),
+            vec![Complex::new(100.0, -200.0), Complex::new(-32768.0, 32767.0)]
+        );
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_float_wav_with_auxi_chunk() {
+        let mut auxi = vec![0u8; 32];
+        auxi.extend_from_slice(&100_000_000u32.to_le_bytes());
+        auxi.extend_from_slice(&[0u8; 4]);
+        let mut extra_chunks = b"auxi".to_vec();
+        extra_chunks.extend_from_slice(&u32::try_from(auxi.len()).unwrap().to_le_bytes());
+        extra_chunks.extend_from_slice(&auxi);
+
+        let mut data = Vec::new();
+        for value in [0.5f32, -0.25] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        let path = write_temp_wav("f32_auxi", &build_wav(FORMAT_IEEE_FLOAT, 32, &extra_chunks, &data));
+        let mut reader = SdrWavReader::builder()
+            .file_path(&path)
+            .samples_per_chunk(1)
+            .build()
+            .expect("Failed to create SdrWavReader");
+        assert_eq!(reader.center_freq_hz(), Some(100_000_000));
+        assert_eq!(reader.read_next_chunk_complexf64().unwrap().unwrap(), vec![Complex::new(0.5, -0.25)]);
+        assert_eq!(reader.read_next_chunk_complexf64().unwrap(), None);
+    }
+
+    #[test]
+    fn test_reject_mono_wav() {
+        let mut wav = build_wav(FORMAT_PCM, 16, &[], &[0u8; 4]);
+        // Patch the channel count in the fmt chunk
+        wav[22] = 1;
+        let path = write_temp_wav("mono", &wav);
+        let error = SdrWavReader::builder()
+            .file_path(&path)
+            .samples_per_chunk(1)
+            .build()
+            .err()
+            .expect("Mono WAV files should be rejected");
+        assert!(matches!(error, SdrReaderError::InvalidMetadata(_)));
+    }
+
+    #[test]
+    fn test_oversized_chunks() {
+        // An unknown chunk that claims to be almost 4 GiB long, in a file that ends right after it
+        let mut wav = build_wav(FORMAT_PCM, 16, b"junk\xF0\xFF\xFF\xFF", &[]);
+        wav.truncate(wav.len() - 20);
+        let error = WavHeader::parse(&mut wav.as_slice()).expect_err("Truncated WAV files should be rejected");
+        assert!(matches!(error, SdrReaderError::Io(why) if why.kind() == ErrorKind::UnexpectedEof));
+
+        // A fmt chunk of that size is rejected before it is read
+        let mut wav = build_wav(FORMAT_PCM, 16, &[], &[]);
+        wav[16..20].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        let error = WavHeader::parse(&mut wav.as_slice()).expect_err("Oversized fmt chunks should be rejected");
+        assert!(matches!(error, SdrReaderError::InvalidMetadata(_)));
+    }
+}
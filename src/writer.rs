@@ -0,0 +1,371 @@
+//! Writing complex samples back into raw IQ files.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use bon::bon;
+use num_complex::Complex;
+use crate::decode::DecodeOptions;
+use crate::sigmf::{format_datatype, meta_json};
+use crate::{CaptureMeta, Endianness, SampleType, SdrReaderError};
+
+/// Writes complex samples into a raw IQ file, the counterpart of [`SdrFileReader`](crate::SdrFileReader).
+///
+/// By default, integer samples are expected in the range [-1.0, 1.0], the same convention the reader
+/// uses with `normalize` and `center_unsigned` enabled. Each component is scaled by the full-scale value of the
+/// sample type, rounded and clamped to the representable range, so out-of-range values saturate instead of wrapping around.
+/// The `normalize` and `center_unsigned` options select the other conventions of the reader.
+/// Float sample types are written unchanged.
+/// A `SigMF` recording is written by finishing the writer with [`SdrFileWriter::finish_sigmf`].
+///
+/// # Example
+/// ```no_run
+/// use num_complex::Complex;
+/// use sdr_iq_file_reader::{SdrFileWriter, SampleType};
+/// let mut writer = SdrFileWriter::builder()
+///     .file_path("capture.raw")
+///     .sample_type(SampleType::I16)
+///     .build()
+///     .expect("Failed to create SdrFileWriter");
+/// writer.write_chunk_complexf32(&[Complex::new(0.5, -0.5)]).unwrap();
+/// writer.finish().unwrap();
+/// ```
+pub struct SdrFileWriter<W: Write = File> {
+    writer: BufWriter<W>,
+    /// The format the samples are encoded in, the inverse of decoding with these options
+    format: DecodeOptions,
+    buffer: Vec<u8>,
+    /// The path of the file, if the writer was created with [`SdrFileWriter::builder`]
+    file_path: Option<PathBuf>,
+}
+
+#[bon]
+impl SdrFileWriter {
+    /// Create a new `SdrFileWriter` that creates (or truncates) the file at `file_path`.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if the file could not be created
+    #[builder]
+    pub fn new(
+        file_path: impl AsRef<Path>,
+        sample_type: SampleType,
+        /// The byte order of multi-byte samples. Defaults to [`Endianness::Little`].
+        #[builder(default)] endianness: Endianness,
+        /// Add the midpoint (127.5 for `U8`, 32767.5 for `U16`) to unsigned samples, the inverse of the reader's `center_unsigned`.
+        /// Defaults to true.
+        #[builder(default = true)] center_unsigned: bool,
+        /// Multiply integer samples by the full-scale value of the sample type, the inverse of the reader's `normalize`.
+        /// If disabled, the samples are written as raw integer values. Defaults to true.
+        #[builder(default = true)] normalize: bool,
+    ) -> Result<Self, SdrReaderError> {
+        let file = File::create(&file_path)?;
+        let mut writer = SdrFileWriter::from_writer(file, sample_type)
+            .endianness(endianness)
+            .center_unsigned(center_unsigned)
+            .normalize(normalize)
+            .build();
+        writer.file_path = Some(file_path.as_ref().to_path_buf());
+        Ok(writer)
+    }
+
+    /// Flush the buffered samples, close the file and write a `.sigmf-meta` file next to it, which describes
+    /// the samples with the `core:datatype` of the sample type and byte order, and the `core:sample_rate`,
+    /// `core:frequency` and `core:datetime` of `meta`. The metadata file has the path of the data file with the
+    /// extension replaced, so naming the data file `capture.sigmf-data` gives a pair
+    /// [`SdrFileReader::from_sigmf`](crate::SdrFileReader::from_sigmf) reads back.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use num_complex::Complex;
+    /// use sdr_iq_file_reader::{CaptureMeta, SdrFileWriter, SampleType};
+    /// let mut writer = SdrFileWriter::builder()
+    ///     .file_path("capture.sigmf-data")
+    ///     .sample_type(SampleType::I16)
+    ///     .build()
+    ///     .expect("Failed to create SdrFileWriter");
+    /// writer.write_chunk_complexf32(&[Complex::new(0.5, -0.5)]).unwrap();
+    /// let meta = CaptureMeta { timestamp: None, center_freq_hz: 433_920_000, sample_rate_hz: 1_000_000 };
+    /// writer.finish_sigmf(meta).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if `SigMF` has no datatype for the sample type (`I24` and `F16`),
+    ///   or the writer was not created from a file path
+    /// - `SdrReaderError::Io` if there was an error writing either file
+    // The metadata is taken by value like the writer itself, as neither is needed once the recording is finished
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn finish_sigmf(self, meta: CaptureMeta) -> Result<(), SdrReaderError> {
+        let datatype = format_datatype(self.format.sample_type, &self.format.endianness).ok_or_else(|| {
+            SdrReaderError::InvalidConfig(format!("SigMF has no datatype for {} samples", self.format.sample_type))
+        })?;
+        let meta_path = self
+            .file_path
+            .as_ref()
+            .map(|file_path| file_path.with_extension("sigmf-meta"))
+            .ok_or_else(|| SdrReaderError::InvalidConfig("SigMF metadata can only be written next to a file path".to_string()))?;
+        // Dropping the file closes it
+        self.finish()?;
+        std::fs::write(meta_path, meta_json(&datatype, &meta))?;
+        Ok(())
+    }
+}
+
+#[bon]
+impl<W: Write> SdrFileWriter<W> {
+    /// Create a new `SdrFileWriter` that writes samples into any [`Write`] sink instead of a file path.
+    #[builder(finish_fn = build)]
+    pub fn from_writer(
+        #[builder(start_fn)] writer: W,
+        #[builder(start_fn)] sample_type: SampleType,
+        /// The byte order of multi-byte samples. Defaults to [`Endianness::Little`].
+        #[builder(default)] endianness: Endianness,
+        /// Add the midpoint (127.5 for `U8`, 32767.5 for `U16`) to unsigned samples, the inverse of the reader's `center_unsigned`.
+        /// Defaults to true.
+        #[builder(default = true)] center_unsigned: bool,
+        /// Multiply integer samples by the full-scale value of the sample type, the inverse of the reader's `normalize`.
+        /// If disabled, the samples are written as raw integer values. Defaults to true.
+        #[builder(default = true)] normalize: bool,
+    ) -> Self {
+        SdrFileWriter {
+            writer: BufWriter::new(writer),
+            format: DecodeOptions {
+                sample_type,
+                endianness,
+                center_unsigned,
+                normalize,
+                swap_iq: false,
+                conjugate: false,
+                full_scale: None,
+                bit_shift: 0,
+                offset_binary: false,
+                byte_table: None,
+            },
+            buffer: Vec::new(),
+            file_path: None,
+        }
+    }
+}
+
+impl<W: Write> SdrFileWriter<W> {
+    /// Encode and write a chunk of Complex<f32> samples.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if the sample type is `F16` and the `half` feature is disabled
+    /// - `SdrReaderError::Io` if there was an error writing
+    pub fn write_chunk_complexf32(&mut self, samples: &[Complex<f32>]) -> Result<(), SdrReaderError> {
+        self.check_encodable()?;
+        self.buffer.clear();
+        for sample in samples {
+            self.encode_component(f64::from(sample.re));
+            self.encode_component(f64::from(sample.im));
+        }
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    /// Encode and write a chunk of Complex<f64> samples.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::InvalidConfig` if the sample type is `F16` and the `half` feature is disabled
+    /// - `SdrReaderError::Io` if there was an error writing
+    pub fn write_chunk_complexf64(&mut self, samples: &[Complex<f64>]) -> Result<(), SdrReaderError> {
+        self.check_encodable()?;
+        self.buffer.clear();
+        for sample in samples {
+            self.encode_component(sample.re);
+            self.encode_component(sample.im);
+        }
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    /// Flush the buffered samples to the underlying writer.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error writing
+    pub fn flush(&mut self) -> Result<(), SdrReaderError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Flush the buffered samples and return the underlying writer.
+    /// Dropping the writer also flushes it, but silently ignores any error, so prefer calling this method.
+    ///
+    /// # Errors
+    /// - `SdrReaderError::Io` if there was an error writing
+    pub fn finish(self) -> Result<W, SdrReaderError> {
+        self.writer.into_inner().map_err(|why| SdrReaderError::Io(why.into_error()))
+    }
+
+    /// Check that the sample type can be encoded with the enabled features.
+    fn check_encodable(&self) -> Result<(), SdrReaderError> {
+        if !cfg!(feature = "half") && self.format.sample_type == SampleType::F16 {
+            return Err(SdrReaderError::InvalidConfig("Encoding F16 samples requires the half feature".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Append the encoded bytes of one component to the internal buffer.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn encode_component(&mut self, value: f64) {
+        let (endianness, sample_type) = (&self.format.endianness, self.format.sample_type);
+        let raw = value * self.format.full_scale().unwrap_or(1.0) + self.format.center_offset();
+        // Clamp explicitly, so out-of-range values saturate at the limits of the sample type instead of wrapping around
+        let integer = |min: f64, max: f64| raw.round().clamp(min, max);
+        match sample_type {
+            SampleType::U8 => self.buffer.push(integer(0.0, 255.0) as u8),
+            SampleType::I8 => self.buffer.push((integer(-128.0, 127.0) as i8).to_le_bytes()[0]),
+            SampleType::U16 => self.buffer.extend_from_slice(&endianness.u16_to_bytes(integer(0.0, 65535.0) as u16)),
+            SampleType::I16 => self.buffer.extend_from_slice(&endianness.i16_to_bytes(integer(-32768.0, 32767.0) as i16)),
+            SampleType::I24 => self.buffer.extend_from_slice(&endianness.i24_to_bytes(integer(-8_388_608.0, 8_388_607.0) as i32)),
+            #[cfg(feature = "half")]
+            SampleType::F16 => self.buffer.extend_from_slice(&endianness.f16_to_bytes(half::f16::from_f64(raw))),
+            // Rejected by `check_encodable` before any component is encoded
+            #[cfg(not(feature = "half"))]
+            SampleType::F16 => {}
+            SampleType::F32 => self.buffer.extend_from_slice(&endianness.f32_to_bytes(raw as f32)),
+            SampleType::F64 => self.buffer.extend_from_slice(&endianness.f64_to_bytes(raw)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::SdrFileReader;
+
+    #[test]
+    fn test_round_trip_i16() {
+        let samples = (0..100u8)
+            .map(|n| Complex::from_polar(0.9f32, f32::from(n) / 10.0))
+            .collect::<Vec<_>>();
+        let file_path = std::env::temp_dir().join("sdr_iq_file_reader_round_trip_i16.raw");
+        let mut writer = SdrFileWriter::builder()
+            .file_path(&file_path)
+            .sample_type(SampleType::I16)
+            .build()
+            .expect("Failed to create SdrFileWriter");
+        writer.write_chunk_complexf32(&samples[..60]).unwrap();
+        writer.write_chunk_complexf32(&samples[60..]).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = SdrFileReader::builder()
+            .file_path(&file_path)
+            .samples_per_chunk(100)
+            .sample_type(SampleType::I16)
+            .normalize(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        let read_back = reader.read_next_chunk_complexf32().unwrap().unwrap();
+        assert_eq!(read_back.len(), samples.len());
+        for (read, written) in read_back.iter().zip(&samples) {
+            // Half a quantization step per component
+            assert!((read.re - written.re).abs() <= 0.5 / 32768.0);
+            assert!((read.im - written.im).abs() <= 0.5 / 32768.0);
+        }
+    }
+
+    #[test]
+    fn test_finish_sigmf() {
+        let samples = [Complex::new(0.5f32, -0.25), Complex::new(-1.0, 0.75)];
+        let data_path = std::env::temp_dir().join(format!("sdr_iq_file_reader_finish_sigmf_{}.sigmf-data", std::process::id()));
+        let meta_path = data_path.with_extension("sigmf-meta");
+        let meta = CaptureMeta {
+            timestamp: Some(std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1_727_574_738_250)),
+            center_freq_hz: 433_920_000,
+            sample_rate_hz: 1_000_000,
+        };
+        let mut writer = SdrFileWriter::builder()
+            .file_path(&data_path)
+            .sample_type(SampleType::I16)
+            .endianness(Endianness::Big)
+            .build()
+            .expect("Failed to create SdrFileWriter");
+        writer.write_chunk_complexf32(&samples).unwrap();
+        writer.finish_sigmf(meta.clone()).unwrap();
+
+        let mut reader = SdrFileReader::from_sigmf(&meta_path, 2)
+            .normalize(true)
+            .build()
+            .expect("Failed to open SigMF recording");
+        assert_eq!(reader.sigmf_meta().unwrap().datatype, "ci16_be");
+        assert_eq!(reader.metadata(), Some(&meta));
+        assert_eq!(reader.read_next_chunk_complexf32().unwrap().unwrap(), samples);
+
+        let writer = SdrFileWriter::builder().file_path(&data_path).sample_type(SampleType::I24).build().unwrap();
+        assert!(matches!(writer.finish_sigmf(meta.clone()), Err(SdrReaderError::InvalidConfig(_))));
+        // The path of a file passed to from_writer is unknown
+        let writer = SdrFileWriter::from_writer(File::create(&data_path).unwrap(), SampleType::I16).build();
+        assert!(matches!(writer.finish_sigmf(meta), Err(SdrReaderError::InvalidConfig(_))));
+        std::fs::remove_file(&data_path).unwrap();
+        std::fs::remove_file(&meta_path).unwrap();
+    }
+
+    #[test]
+    fn test_out_of_range_saturates() {
+        let samples = [Complex::new(2.0, -2.0), Complex::new(1.0, -1.0)];
+        let mut writer = SdrFileWriter::from_writer(Vec::new(), SampleType::I16).build();
+        writer.write_chunk_complexf64(&samples).unwrap();
+        let bytes = writer.finish().unwrap();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), 2, SampleType::I16).build().unwrap();
+        let read_back = reader.read_next_chunk_complexf64().unwrap().unwrap();
+        assert_eq!(read_back, vec![Complex::new(32767.0, -32768.0), Complex::new(32767.0, -32768.0)]);
+
+        let mut writer = SdrFileWriter::from_writer(Vec::new(), SampleType::U8).build();
+        writer.write_chunk_complexf64(&[Complex::new(1.5, -1.5)]).unwrap();
+        assert_eq!(writer.finish().unwrap(), vec![255, 0]);
+    }
+
+    #[test]
+    fn test_raw_integer_convention() {
+        let mut writer = SdrFileWriter::from_writer(Vec::new(), SampleType::U8)
+            .center_unsigned(false)
+            .normalize(false)
+            .build();
+        writer.write_chunk_complexf64(&[Complex::new(17.0, 300.0)]).unwrap();
+        assert_eq!(writer.finish().unwrap(), vec![17, 255]);
+
+        // Normalized but not centered, like the reader with only `normalize` enabled
+        let mut writer = SdrFileWriter::from_writer(Vec::new(), SampleType::U16).center_unsigned(false).build();
+        writer.write_chunk_complexf64(&[Complex::new(0.0, 1.0)]).unwrap();
+        assert_eq!(writer.finish().unwrap(), vec![0, 0, 255, 255]);
+    }
+
+    /// Write `samples` with the sample format returned by `format` and read them back normalized.
+    fn round_trip(format: impl Fn() -> (SampleType, Endianness), samples: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let (sample_type, endianness) = format();
+        let mut writer = SdrFileWriter::from_writer(Vec::new(), sample_type).endianness(endianness).build();
+        writer.write_chunk_complexf64(samples).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let (sample_type, endianness) = format();
+        let mut reader = SdrFileReader::from_reader(Cursor::new(bytes), samples.len(), sample_type)
+            .endianness(endianness)
+            .center_unsigned(true)
+            .normalize(true)
+            .build()
+            .expect("Failed to create SdrFileReader");
+        reader.read_next_chunk_complexf64().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_all_types() {
+        let samples = vec![Complex::new(0.25, -0.75), Complex::new(-1.0, 0.5)];
+        let formats: [fn() -> (SampleType, Endianness); 6] = [
+            || (SampleType::U8, Endianness::Little),
+            || (SampleType::I8, Endianness::Little),
+            || (SampleType::U16, Endianness::Big),
+            || (SampleType::I24, Endianness::Big),
+            || (SampleType::F32, Endianness::Little),
+            || (SampleType::F64, Endianness::Big),
+        ];
+        for format in formats {
+            let read_back = round_trip(format, &samples);
+            assert_eq!(read_back.len(), samples.len());
+            for (read, written) in read_back.iter().zip(&samples) {
+                assert!((read - written).norm() < 1e-2);
+            }
+        }
+    }
+}